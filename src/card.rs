@@ -1,14 +1,23 @@
 use std::fmt;
+use std::str::FromStr;
 
 use crate::game_state::GameState;
-use crate::rank::{Rank, ALL_RANKS};
+use crate::parse::{ParseError, ParseErrorKind};
+use crate::rank::{Rank, ALL_RANKS, NUM_RANKS};
 use crate::score::Score;
 use crate::suit::{Suit, ALL_SUITS};
 use crate::utils;
 
-const NUM_JOKERS_IN_DECK: usize = 4;
-const JOKER_SCORE: Score = Score(25);
+const OFFICIAL_COPIES_PER_CARD: usize = 2;
+const OFFICIAL_NUM_JOKERS: usize = 6;
 const JOKER_STRING: &str = "Joker";
+const JOKER_CHARS: [char; 2] = ['*', '\u{1F0CF}'];
+/// Text and emoji presentation selectors, which phone keyboards often add after glyphs.
+const VARIATION_SELECTORS: [char; 2] = ['\u{FE0E}', '\u{FE0F}'];
+
+/// Number of different cards, counting every joker as the same card. See `Card::index`.
+pub const NUM_CARD_INDICES: usize = ALL_SUITS.len() * NUM_RANKS + 1;
+const JOKER_INDEX: u8 = (NUM_CARD_INDICES - 1) as u8;
 
 #[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Copy, Clone)]
 pub enum Card {
@@ -22,6 +31,15 @@ pub struct NormalCard {
     rank: Rank,
 }
 
+/// How many of each card make up a deck.
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DeckSpec {
+    /// Number of copies of each suit/rank combination.
+    pub copies_per_card: usize,
+    pub num_jokers: usize,
+}
+
 impl Card {
     pub fn new(suit: Suit, rank: Rank) -> Self {
         Card::Normal(NormalCard { suit, rank })
@@ -31,10 +49,14 @@ impl Card {
         Card::Joker
     }
 
-    pub fn score(&self) -> Score {
+    /// Points for this card if it is left in hand at the end of a round.
+    pub fn score(&self, game_state: &GameState) -> Score {
         match self {
-            Card::Normal(card) => card.rank.score(),
-            Card::Joker => JOKER_SCORE,
+            Card::Joker => game_state.rules().joker_score,
+            Card::Normal(card) if game_state.is_rank_wild(card.rank) => {
+                game_state.rules().wild_score
+            }
+            Card::Normal(card) => card.score(),
         }
     }
 
@@ -63,13 +85,111 @@ impl Card {
     }
 
     pub fn try_from(string: &str) -> Option<Self> {
-        if string == JOKER_STRING {
+        string.parse().ok()
+    }
+
+    /// A compact number for the card, below `NUM_CARD_INDICES`. Normal cards are ordered by suit
+    /// then rank, and the joker comes last.
+    pub fn index(&self) -> u8 {
+        match self {
+            Card::Normal(card) => (card.suit.index() * NUM_RANKS + card.rank.index()) as u8,
+            Card::Joker => JOKER_INDEX,
+        }
+    }
+
+    /// The card with the given `index`, if it is in range.
+    pub fn from_index(index: u8) -> Option<Self> {
+        if index == JOKER_INDEX {
             return Some(Card::Joker);
         }
-        let (rank_str, suit_char) = utils::split_last_char(string)?;
-        let suit = Suit::try_from(suit_char)?;
-        let rank = Rank::try_from(&rank_str)?;
-        Some(Card::new(suit, rank))
+        let index = index as usize;
+        let suit = *ALL_SUITS.get(index / NUM_RANKS)?;
+        Some(Card::new(suit, ALL_RANKS[index % NUM_RANKS]))
+    }
+
+    /// The card with its suit as a glyph, e.g. "10\u{2666}".
+    pub fn glyph_string(&self) -> String {
+        match self {
+            Card::Normal(card) => format!("{}{}", card.rank, card.suit.glyph()),
+            Card::Joker => JOKER_STRING.to_string(),
+        }
+    }
+
+    /// The card written out in full, e.g. "Ten of Diamonds".
+    pub fn long_name(&self) -> String {
+        match self {
+            Card::Normal(card) => format!("{} of {}", card.rank.name(), card.suit.name()),
+            Card::Joker => JOKER_STRING.to_string(),
+        }
+    }
+
+    /// Parse a single card, allowing anything `read_lenient` does, plus surrounding whitespace.
+    pub fn parse_lenient(string: &str) -> Result<Self, ParseError> {
+        let trimmed = string.trim_start();
+        let offset = string.chars().count() - trimmed.chars().count();
+        let chars = trimmed.trim_end().chars().collect::<Vec<_>>();
+        let mut index = 0;
+        let card = Card::read_lenient(&chars, &mut index).map_err(|e| e.offset(offset))?;
+        if index < chars.len() {
+            let token = chars.iter().collect::<String>();
+            return Err(ParseError::new(&token, ParseErrorKind::ExtraCharacters).offset(offset));
+        }
+        Ok(card)
+    }
+
+    /// Read a card from `chars` starting at `index`, and move `index` past it. Case is ignored,
+    /// suits can be glyphs, tens can be written "T", and "*" is a joker. Variation selectors after
+    /// the card are skipped.
+    pub(crate) fn read_lenient(chars: &[char], index: &mut usize) -> Result<Self, ParseError> {
+        let card = Card::read_lenient_card(chars, index)?;
+        while chars
+            .get(*index)
+            .is_some_and(|c| VARIATION_SELECTORS.contains(c))
+        {
+            *index += 1;
+        }
+        Ok(card)
+    }
+
+    fn read_lenient_card(chars: &[char], index: &mut usize) -> Result<Self, ParseError> {
+        let start = *index;
+        // The rest of the word, for error messages
+        let token = || {
+            chars[start..]
+                .iter()
+                .take_while(|c| !c.is_whitespace() && **c != ',')
+                .collect::<String>()
+        };
+        let error = |kind| ParseError::new(&token(), kind).offset(start);
+        let first = *chars
+            .get(start)
+            .ok_or_else(|| error(ParseErrorKind::Empty))?;
+        if JOKER_CHARS.contains(&first) {
+            *index += 1;
+            return Ok(Card::Joker);
+        }
+        let joker_len = JOKER_STRING.chars().count();
+        let word = chars[start..].iter().take(joker_len).collect::<String>();
+        if word.eq_ignore_ascii_case(JOKER_STRING) {
+            *index += joker_len;
+            return Ok(Card::Joker);
+        }
+        let (rank, rank_len) = match (first.to_ascii_uppercase(), chars.get(start + 1)) {
+            ('1', Some('0')) => (Rank::Ten, 2),
+            ('T', _) => (Rank::Ten, 1),
+            (c, _) => (
+                c.to_string()
+                    .parse()
+                    .map_err(|_| error(ParseErrorKind::BadRank))?,
+                1,
+            ),
+        };
+        let suit = chars
+            .get(start + rank_len)
+            .and_then(|&c| Suit::try_from_lenient(c))
+            .ok_or_else(|| error(ParseErrorKind::BadSuit))?;
+        *index += rank_len + 1;
+        Ok(Card::new(suit, rank))
     }
 }
 
@@ -91,26 +211,106 @@ impl NormalCard {
     }
 }
 
-pub(crate) fn full_deck() -> impl Iterator<Item = Card> {
+impl DeckSpec {
+    pub fn new(copies_per_card: usize, num_jokers: usize) -> Self {
+        DeckSpec {
+            copies_per_card,
+            num_jokers,
+        }
+    }
+
+    /// The official Five Crowns deck: two copies of each card plus six jokers, 116 cards in total.
+    pub fn official() -> Self {
+        DeckSpec::new(OFFICIAL_COPIES_PER_CARD, OFFICIAL_NUM_JOKERS)
+    }
+
+    pub fn num_cards(&self) -> usize {
+        ALL_SUITS.len() * ALL_RANKS.len() * self.copies_per_card + self.num_jokers
+    }
+}
+
+impl Default for DeckSpec {
+    fn default() -> Self {
+        DeckSpec::official()
+    }
+}
+
+pub(crate) fn full_deck(spec: &DeckSpec) -> impl Iterator<Item = Card> {
+    let copies_per_card = spec.copies_per_card;
     ALL_SUITS
         .iter()
         .flat_map(move |&suit| {
-            ALL_RANKS
-                .iter()
-                .map(move |&rank| Card::Normal(NormalCard::new(suit, rank)))
+            ALL_RANKS.iter().flat_map(move |&rank| {
+                std::iter::repeat_n(Card::Normal(NormalCard::new(suit, rank)), copies_per_card)
+            })
         })
-        .chain(std::iter::repeat(Card::Joker).take(NUM_JOKERS_IN_DECK))
+        .chain(std::iter::repeat_n(Card::Joker, spec.num_jokers))
+}
+
+impl FromStr for Card {
+    type Err = ParseError;
+
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        if string == JOKER_STRING {
+            return Ok(Card::Joker);
+        }
+        let error = |kind| ParseError::new(string, kind);
+        let (rank_str, suit_char) =
+            utils::split_last_char(string).ok_or_else(|| error(ParseErrorKind::Empty))?;
+        let suit = Suit::try_from(suit_char).ok_or_else(|| error(ParseErrorKind::BadSuit))?;
+        let rank = rank_str
+            .parse()
+            .map_err(|_| error(ParseErrorKind::BadRank))?;
+        Ok(Card::new(suit, rank))
+    }
 }
 
 impl fmt::Display for Card {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Card::Normal(card) => write!(f, "{}{}", card.rank, card.suit),
+            Card::Normal(card) => card.fmt(f),
             Card::Joker => write!(f, "{}", JOKER_STRING),
         }
     }
 }
 
+impl fmt::Display for NormalCard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.rank, self.suit)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Card {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        utils::serialize_display(self, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Card {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        utils::deserialize_from_str(deserializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for NormalCard {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        utils::serialize_display(self, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for NormalCard {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let card: Card = utils::deserialize_from_str(deserializer)?;
+        card.normal()
+            .cloned()
+            .ok_or_else(|| serde::de::Error::custom("expected a card other than a joker"))
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -170,4 +370,147 @@ mod test {
         // Garbage
         assert_eq!(None, Card::try_from("Hello"));
     }
+
+    #[test]
+    fn test_score() {
+        use crate::rules::RuleSet;
+        let game_state = GameState::new(Rank::Five);
+        assert_eq!(Card::try_from("3S").unwrap().score(&game_state), Score(3));
+        assert_eq!(Card::try_from("KH").unwrap().score(&game_state), Score(13));
+        assert_eq!(Card::try_from("5D").unwrap().score(&game_state), Score(20));
+        assert_eq!(Card::joker().score(&game_state), Score(50));
+
+        let rules = RuleSet {
+            joker_score: Score(25),
+            ..RuleSet::official()
+        };
+        let game_state = GameState::with_rules(Rank::Five, rules);
+        assert_eq!(Card::joker().score(&game_state), Score(25));
+    }
+
+    #[test]
+    fn test_full_deck() {
+        let spec = DeckSpec::official();
+        let deck = full_deck(&spec).collect::<Vec<_>>();
+        assert_eq!(deck.len(), 116);
+        assert_eq!(deck.len(), spec.num_cards());
+        assert_eq!(deck.iter().filter(|card| card.is_joker()).count(), 6);
+        let three_of_spades = Card::new(Suit::Spade, Rank::Three);
+        assert_eq!(
+            deck.iter().filter(|&&card| card == three_of_spades).count(),
+            2
+        );
+
+        let spec = DeckSpec::new(1, 3);
+        assert_eq!(full_deck(&spec).count(), 58);
+        assert_eq!(spec.num_cards(), 58);
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!(Ok(Card::new(Suit::Diamond, Rank::Ten)), "10D".parse());
+        assert_eq!(Ok(Card::Joker), "Joker".parse());
+        assert_eq!(
+            Err(ParseError::new("11C", ParseErrorKind::BadRank)),
+            "11C".parse::<Card>()
+        );
+        assert_eq!(
+            Err(ParseError::new("7A", ParseErrorKind::BadSuit)),
+            "7A".parse::<Card>()
+        );
+        assert_eq!(
+            Err(ParseError::new("", ParseErrorKind::Empty)),
+            "".parse::<Card>()
+        );
+    }
+
+    #[test]
+    fn test_renderings() {
+        let card = Card::new(Suit::Diamond, Rank::Ten);
+        assert_eq!(card.to_string(), "10D");
+        assert_eq!(card.glyph_string(), "10\u{2666}");
+        assert_eq!(card.long_name(), "Ten of Diamonds");
+        assert_eq!(
+            Card::new(Suit::Star, Rank::Queen).glyph_string(),
+            "Q\u{2605}"
+        );
+        assert_eq!(Card::Joker.glyph_string(), "Joker");
+        assert_eq!(Card::Joker.long_name(), "Joker");
+    }
+
+    #[test]
+    fn test_parse_lenient() {
+        let ten_of_diamonds = Ok(Card::new(Suit::Diamond, Rank::Ten));
+        assert_eq!(Card::parse_lenient("10D"), ten_of_diamonds);
+        assert_eq!(Card::parse_lenient("10d"), ten_of_diamonds);
+        assert_eq!(Card::parse_lenient("td"), ten_of_diamonds);
+        assert_eq!(Card::parse_lenient(" 10\u{2666} "), ten_of_diamonds);
+        assert_eq!(Card::parse_lenient("10\u{2662}"), ten_of_diamonds);
+        assert_eq!(
+            Card::parse_lenient("k\u{2605}"),
+            Ok(Card::new(Suit::Star, Rank::King))
+        );
+        assert_eq!(Card::parse_lenient("10\u{2666}\u{FE0F}"), ten_of_diamonds);
+        assert_eq!(Card::parse_lenient("10\u{2666}\u{FE0E}"), ten_of_diamonds);
+        assert_eq!(Card::parse_lenient("*"), Ok(Card::Joker));
+        assert_eq!(Card::parse_lenient("\u{1F0CF}\u{FE0F}"), Ok(Card::Joker));
+        assert_eq!(Card::parse_lenient("joker"), Ok(Card::Joker));
+        assert_eq!(Card::parse_lenient("JOKER"), Ok(Card::Joker));
+        assert_eq!(
+            Card::parse_lenient(" 11c"),
+            Err(ParseError::new("11c", ParseErrorKind::BadRank).offset(1))
+        );
+        assert_eq!(
+            Card::parse_lenient("7a"),
+            Err(ParseError::new("7a", ParseErrorKind::BadSuit))
+        );
+        assert_eq!(
+            Card::parse_lenient("7"),
+            Err(ParseError::new("7", ParseErrorKind::BadSuit))
+        );
+        assert_eq!(
+            Card::parse_lenient("  "),
+            Err(ParseError::new("", ParseErrorKind::Empty).offset(2))
+        );
+        assert_eq!(
+            Card::parse_lenient("3S4S"),
+            Err(ParseError::new("3S4S", ParseErrorKind::ExtraCharacters))
+        );
+    }
+
+    #[test]
+    fn test_index() {
+        let mut seen = Vec::new();
+        for card in full_deck(&DeckSpec::new(1, 1)) {
+            let index = card.index();
+            assert!((index as usize) < NUM_CARD_INDICES);
+            assert_eq!(Card::from_index(index), Some(card));
+            seen.push(index);
+        }
+        seen.sort();
+        assert_eq!(seen, (0..NUM_CARD_INDICES as u8).collect::<Vec<_>>());
+        assert_eq!(Card::from_index(NUM_CARD_INDICES as u8), None);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let card = Card::new(Suit::Diamond, Rank::Ten);
+        assert_eq!(serde_json::to_string(&card).unwrap(), "\"10D\"");
+        assert_eq!(serde_json::to_string(&Card::Joker).unwrap(), "\"Joker\"");
+        assert_eq!(
+            serde_json::from_str::<Card>("\"KR\"").unwrap(),
+            "KR".parse().unwrap()
+        );
+        assert!(serde_json::from_str::<Card>("\"11C\"").is_err());
+        let normal = *card.normal().unwrap();
+        assert_eq!(serde_json::to_string(&normal).unwrap(), "\"10D\"");
+        assert_eq!(
+            serde_json::from_str::<NormalCard>("\"10D\"").unwrap(),
+            normal
+        );
+        assert!(serde_json::from_str::<NormalCard>("\"Joker\"").is_err());
+        assert_eq!(serde_json::to_string(&Rank::Queen).unwrap(), "\"Q\"");
+        assert_eq!(serde_json::from_str::<Suit>("\"R\"").unwrap(), Suit::Star);
+    }
 }
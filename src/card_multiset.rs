@@ -0,0 +1,149 @@
+use crate::card::{Card, NUM_CARD_INDICES};
+use crate::hand::Hand;
+use std::iter::FromIterator;
+
+/// A hand stored as the number of copies of each card, indexed by `Card::index`. Inserting,
+/// removing and looking up a card are all O(1), and duplicates from multiple decks are counted.
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct CardMultiset {
+    counts: [u32; NUM_CARD_INDICES],
+    len: usize,
+}
+
+impl CardMultiset {
+    pub fn new() -> Self {
+        CardMultiset {
+            counts: [0; NUM_CARD_INDICES],
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Number of copies of `card`.
+    pub fn count(&self, card: Card) -> usize {
+        self.counts[card.index() as usize] as usize
+    }
+
+    pub fn contains(&self, card: Card) -> bool {
+        self.count(card) > 0
+    }
+
+    /// Add a copy of `card`. Panics if there would be more than `u32::MAX` copies.
+    pub fn insert(&mut self, card: Card) {
+        let count = &mut self.counts[card.index() as usize];
+        *count = count.checked_add(1).expect("Too many copies of a card");
+        self.len += 1;
+    }
+
+    /// Remove a copy of `card`. Returns `false` if there wasn't one.
+    pub fn remove(&mut self, card: Card) -> bool {
+        let count = &mut self.counts[card.index() as usize];
+        if *count == 0 {
+            return false;
+        }
+        *count -= 1;
+        self.len -= 1;
+        true
+    }
+
+    /// Every card, with duplicates, in index order.
+    pub fn iter(&self) -> impl '_ + Iterator<Item = Card> {
+        self.counts.iter().enumerate().flat_map(|(index, &count)| {
+            let card = Card::from_index(index as u8).expect("Index out of range");
+            std::iter::repeat_n(card, count as usize)
+        })
+    }
+
+    pub fn to_hand(&self) -> Hand {
+        Hand::new(self.iter().collect())
+    }
+}
+
+impl Default for CardMultiset {
+    fn default() -> Self {
+        CardMultiset::new()
+    }
+}
+
+impl FromIterator<Card> for CardMultiset {
+    fn from_iter<I: IntoIterator<Item = Card>>(iter: I) -> Self {
+        let mut cards = CardMultiset::new();
+        for card in iter {
+            cards.insert(card);
+        }
+        cards
+    }
+}
+
+impl From<&[Card]> for CardMultiset {
+    fn from(cards: &[Card]) -> Self {
+        cards.iter().cloned().collect()
+    }
+}
+
+impl From<&Hand> for CardMultiset {
+    fn from(hand: &Hand) -> Self {
+        CardMultiset::from(hand.cards.as_slice())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::card::{full_deck, DeckSpec};
+
+    #[test]
+    fn test_insert_remove() {
+        let card = Card::try_from("10D").unwrap();
+        let mut cards = CardMultiset::new();
+        assert!(!cards.contains(card));
+        cards.insert(card);
+        cards.insert(card);
+        cards.insert(Card::Joker);
+        assert_eq!(cards.len(), 3);
+        assert_eq!(cards.count(card), 2);
+        assert!(cards.remove(card));
+        assert!(cards.contains(card));
+        assert!(cards.remove(card));
+        assert!(!cards.contains(card));
+        assert!(!cards.remove(card));
+        assert_eq!(cards.len(), 1);
+        assert_eq!(cards.iter().collect::<Vec<_>>(), vec![Card::Joker]);
+    }
+
+    #[test]
+    fn test_hand_conversions() {
+        let hand = Hand::try_from("Joker,KR,3S,KR,10D").unwrap();
+        let cards = CardMultiset::from(&hand);
+        assert_eq!(cards.len(), 5);
+        assert_eq!(
+            cards.to_hand(),
+            Hand::try_from("3S,10D,KR,KR,Joker").unwrap()
+        );
+        // Order doesn't matter
+        let shuffled = Hand::try_from("KR,10D,KR,Joker,3S").unwrap();
+        assert_eq!(CardMultiset::from(&shuffled), cards);
+    }
+
+    #[test]
+    fn test_full_deck() {
+        let spec = DeckSpec::official();
+        let deck = full_deck(&spec).collect::<CardMultiset>();
+        assert_eq!(deck.len(), spec.num_cards());
+        assert_eq!(deck.count(Card::Joker), spec.num_jokers);
+        assert_eq!(deck.count(Card::try_from("QH").unwrap()), 2);
+
+        // Far more copies of a card than fit in a byte
+        let spec = DeckSpec::new(150, 300);
+        let deck = full_deck(&spec).collect::<CardMultiset>();
+        assert_eq!(deck.count(Card::Joker), 300);
+        assert_eq!(deck.count(Card::try_from("QH").unwrap()), 150);
+    }
+}
@@ -0,0 +1,66 @@
+use crate::card::Card;
+use crate::game::DrawLocation;
+use crate::game_result::GameResult;
+use crate::rank::Rank;
+use crate::record::Move;
+use crate::round_result::RoundResult;
+use crate::rules::ExhaustionPolicy;
+use crate::score::Score;
+use crate::score_group::ScoreGroup;
+
+/// Something that happened during a game.
+///
+/// Events carry everything that happened, including cards drawn from the draw pile that only the
+/// drawing player should see. Observers that pass events on to players need to hide those.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum GameEvent {
+    /// A new round was dealt by `dealer`, and `first_player` is to their left.
+    RoundStarted {
+        round_rank: Rank,
+        dealer: usize,
+        first_player: usize,
+    },
+    /// `player` drew `card` from `location`.
+    Drew {
+        player: usize,
+        location: DrawLocation,
+        card: Card,
+    },
+    /// `player` put `card` on the discard pile, ending their turn.
+    Discarded { player: usize, card: Card },
+    /// `player` went out by laying down `melds`, after discarding.
+    WentOut {
+        player: usize,
+        melds: Vec<ScoreGroup>,
+    },
+    /// `player` took their last turn after someone else went out, after discarding.
+    LastTurn {
+        player: usize,
+        melds: Vec<ScoreGroup>,
+        leftover: Vec<Card>,
+        points: Score,
+    },
+    /// The round is over and has been scored.
+    RoundScored(RoundResult),
+    /// The draw pile ran out, and `num_cards` cards from the discard pile were shuffled to replace
+    /// it.
+    Reshuffled { num_cards: usize },
+    /// The draw pile ran out with no discarded cards to reshuffle into it, so `policy` was applied.
+    DeckExhausted { policy: ExhaustionPolicy },
+    /// The last round is over.
+    GameOver(GameResult),
+    /// The last move was taken back with `Game::undo`. Its events are removed from `Game::events`,
+    /// so this is only sent to observers and never appears in the log.
+    Undone(Move),
+}
+
+/// Receives every event from a `Game` as it happens. See `Game::add_observer`.
+pub trait GameObserver {
+    fn on_event(&mut self, event: &GameEvent);
+}
+
+impl<F: FnMut(&GameEvent)> GameObserver for F {
+    fn on_event(&mut self, event: &GameEvent) {
+        self(event)
+    }
+}
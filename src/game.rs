@@ -1,91 +1,410 @@
-use crate::card::Card;
+use crate::card::{full_deck, Card, DeckSpec};
+use crate::card_multiset::CardMultiset;
+use crate::event::{GameEvent, GameObserver};
+use crate::game_result::GameResult;
 use crate::game_state::GameState;
+use crate::hand::Hand;
+use crate::player_view::{PlayerView, PublicPlayerInfo};
 use crate::rank::Rank;
+use crate::record::{GameRecord, Move};
+use crate::round_result::{PlayerRoundResult, RoundResult};
+use crate::rules::{ExhaustionPolicy, RuleSet};
 use crate::score::Score;
-use crate::score_group::ScoreGroup;
+use crate::score_group::{self, ScoreGroup};
+use crate::snapshot::Snapshot;
+use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
-use rand::thread_rng;
+use rand::{thread_rng, Rng, SeedableRng};
 
 pub struct Game {
     players: Vec<PlayerInfo>,
+    dealer_index: usize,
     current_player_index: usize,
     first_player_gone_out_index: Option<usize>,
     deck: Vec<Card>,
     discard_pile: Vec<Card>,
     state: GameState,
+    phase: Phase,
+    seed: u64,
+    /// Number of shuffles so far. Each shuffle uses its own RNG built from the seed and this count,
+    /// so the two of them are enough to reproduce every later shuffle.
+    num_shuffles: u64,
+    events: Vec<GameEvent>,
+    observers: Vec<Box<dyn GameObserver>>,
+    /// Every move so far, so that the game can be recorded and replayed.
+    moves: Vec<Move>,
+    /// Moves taken back by `undo`, most recent last.
+    undone_moves: Vec<Move>,
+    /// Each player's result for the current round, once they have gone out or had their last turn.
+    round_melds: Vec<Option<PlayerRoundResult>>,
+    round_results: Vec<RoundResult>,
 }
 
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub struct PlayerInfo {
     pub hand: Vec<Card>,
     pub score: Score,
 }
 
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Phase {
+    /// The current player needs to draw a card.
+    AwaitingDraw,
+    /// The current player has drawn and needs to discard, possibly going out.
+    AwaitingDiscard,
+    /// Someone has gone out and the other players are taking their last turns.
+    LastTurns { drawn: bool },
+    /// Every player has had their last turn. Call `Game::next_round` to continue.
+    RoundOver,
+    /// The last round is over.
+    GameOver,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DrawLocation {
     DiscardPile,
     DrawPile,
 }
 
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Action {
     Discard(Card),
     GoOut(Card, Vec<ScoreGroup>),
 }
 
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EndOfRoundAction {
     pub discard: Card,
     pub go_out: Vec<ScoreGroup>,
     pub remaining: Vec<Card>,
 }
 
-type Result<T> = std::result::Result<T, Error>;
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub enum Error {
+    /// The card is not in the current player's hand.
+    CardNotInHand(Card),
+    /// The action is not allowed at this point in the round.
+    WrongPhase,
+    /// There is no card on the discard pile to draw.
+    EmptyDiscardPile,
+    /// The draw pile is empty and there are not enough discarded cards to reshuffle.
+    /// See `ExhaustionPolicy`.
+    DeckExhausted,
+    /// The game has already finished.
+    GameOver,
+    /// The rules don't allow this many players.
+    InvalidPlayerCount(usize),
+    /// A `GameRunner` was given a different number of players than the game has seats.
+    PlayerSeatMismatch { players: usize, seats: usize },
+    /// The deck is too small to deal the biggest round and still start a discard pile.
+    NotEnoughCards { needed: usize, available: usize },
+    /// The player tried to go out with an invalid set of groups.
+    InvalidMeld(MeldError),
+    /// The snapshot doesn't describe a reachable game: its moves don't replay to exactly the
+    /// position it holds.
+    InvalidSnapshot,
+    /// No moves have been made yet.
+    NothingToUndo,
+    /// No moves have been undone since the last move was made.
+    NothingToRedo,
+}
 
-#[derive(Debug, Eq, PartialEq, Ord, PartialOrd)]
-pub struct Error;
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub enum MeldError {
+    /// The groups and remaining cards do not use exactly the cards in the player's hand, minus the
+    /// discard.
+    CardsDoNotMatchHand,
+    /// One of the groups is not valid for the current round.
+    InvalidGroup(score_group::Error),
+}
 
 impl Game {
-    pub fn new(num_players: usize) -> Self {
-        let mut deck = crate::card::full_deck().collect::<Vec<_>>();
-        let mut rng = thread_rng();
-        deck.shuffle(&mut rng);
+    pub fn new(num_players: usize) -> Result<Self> {
+        Game::with_rules(num_players, RuleSet::official())
+    }
+
+    /// A game using the official rules whose shuffles are fully determined by `seed`.
+    pub fn with_seed(num_players: usize, seed: u64) -> Result<Self> {
+        Game::with_rules_and_seed(num_players, RuleSet::official(), seed)
+    }
+
+    /// A game using the official rules apart from the deck.
+    pub fn with_deck_spec(num_players: usize, deck_spec: DeckSpec) -> Result<Self> {
+        Game::with_rules(
+            num_players,
+            RuleSet {
+                deck: deck_spec,
+                ..RuleSet::official()
+            },
+        )
+    }
+
+    pub fn with_rules(num_players: usize, rules: RuleSet) -> Result<Self> {
+        Game::with_rules_and_seed(num_players, rules, thread_rng().gen())
+    }
+
+    /// Returns an error if the rules don't allow `num_players`, or if the deck is too small to deal
+    /// every round to that many players.
+    pub fn with_rules_and_seed(num_players: usize, rules: RuleSet, seed: u64) -> Result<Self> {
+        check_player_count(num_players, &rules)?;
+        let needed = rules.cards_needed(num_players);
+        let available = rules.deck.num_cards();
+        if needed > available {
+            return Err(Error::NotEnoughCards { needed, available });
+        }
         let players = (0..num_players).map(|_| PlayerInfo::new()).collect();
-        let discard_pile = Vec::new();
-        let state = GameState::new(Rank::Three);
+        let state = GameState::with_rules(Rank::Three, rules);
         let mut game = Game {
             players,
+            // The last seat deals first, so the first seat leads the first round
+            dealer_index: num_players - 1,
             current_player_index: 0,
             first_player_gone_out_index: None,
-            deck,
-            discard_pile,
+            deck: Vec::new(),
+            discard_pile: Vec::new(),
             state,
+            phase: Phase::AwaitingDraw,
+            seed,
+            num_shuffles: 0,
+            events: Vec::new(),
+            observers: Vec::new(),
+            moves: Vec::new(),
+            undone_moves: Vec::new(),
+            round_melds: Vec::new(),
+            round_results: Vec::new(),
         };
-        game.deal();
-        game.discard_pile
-            .push(game.deck.pop().expect("Empty deck after dealing"));
-        game
+        game.start_round();
+        Ok(game)
+    }
+
+    /// Restore a game saved with `Game::snapshot`. The event log starts out empty.
+    pub fn from_snapshot(snapshot: Snapshot) -> Result<Self> {
+        let rules = snapshot.rules;
+        let num_players = snapshot.players.len();
+        check_player_count(num_players, &rules)?;
+        let game = Game {
+            players: snapshot.players,
+            dealer_index: snapshot.dealer,
+            current_player_index: snapshot.current_player,
+            first_player_gone_out_index: snapshot.first_out,
+            deck: snapshot.deck,
+            discard_pile: snapshot.discard_pile,
+            state: GameState::with_rules(snapshot.round_rank, rules),
+            phase: snapshot.phase,
+            seed: snapshot.seed,
+            num_shuffles: snapshot.num_shuffles,
+            events: Vec::new(),
+            observers: Vec::new(),
+            moves: snapshot.moves,
+            undone_moves: Vec::new(),
+            round_melds: snapshot.round_melds,
+            round_results: snapshot.round_results,
+        };
+        // `undo` rebuilds the game by replaying its moves, so they have to lead to exactly this
+        // position
+        let replayed = game.record().replay().map_err(|_| Error::InvalidSnapshot)?;
+        if replayed.snapshot() != game.snapshot() {
+            return Err(Error::InvalidSnapshot);
+        }
+        Ok(game)
+    }
+
+    /// The full state of the game, hidden cards included, for saving and restoring later.
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            seed: self.seed,
+            num_shuffles: self.num_shuffles,
+            rules: *self.rules(),
+            round_rank: self.state.round_rank(),
+            dealer: self.dealer_index,
+            current_player: self.current_player_index,
+            first_out: self.first_player_gone_out_index,
+            phase: self.phase,
+            deck: self.deck.clone(),
+            discard_pile: self.discard_pile.clone(),
+            players: self.players.clone(),
+            round_melds: self.round_melds.clone(),
+            round_results: self.round_results.clone(),
+            moves: self.moves.clone(),
+        }
     }
 
-    pub fn state(&mut self) -> GameState {
+    pub fn state(&self) -> GameState {
         self.state
     }
 
-    pub fn draw(&mut self, location: DrawLocation) -> Card {
+    /// The final standings, once the game is over.
+    pub fn result(&self) -> Option<GameResult> {
+        if !self.is_game_over() {
+            return None;
+        }
+        let scores = self.players.iter().map(|p| p.score).collect::<Vec<_>>();
+        Some(GameResult::new(
+            &scores,
+            &self.round_results,
+            self.rules().tie_breaker,
+        ))
+    }
+
+    /// The result of every round that has finished so far.
+    pub fn round_results(&self) -> &[RoundResult] {
+        &self.round_results
+    }
+
+    /// Every event so far, oldest first.
+    pub fn events(&self) -> &[GameEvent] {
+        &self.events
+    }
+
+    /// A record of the game so far. Replaying it reproduces this exact position.
+    pub fn record(&self) -> GameRecord {
+        GameRecord {
+            num_players: self.players.len(),
+            seed: self.seed,
+            rules: *self.rules(),
+            moves: self.moves.clone(),
+        }
+    }
+
+    /// Send every future event to `observer`. It is first sent every event so far, so that it sees
+    /// the whole game no matter when it is added.
+    pub fn add_observer(&mut self, mut observer: Box<dyn GameObserver>) {
+        for event in self.events.iter() {
+            observer.on_event(event);
+        }
+        self.observers.push(observer);
+    }
+
+    /// The seed used for every shuffle in this game. Passing it to `Game::with_rules_and_seed`
+    /// along with the same rules reproduces the game.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn rules(&self) -> &RuleSet {
+        self.state.rules()
+    }
+
+    pub fn phase(&self) -> Phase {
+        self.phase
+    }
+
+    /// Draw a card for the current player. Only allowed at the start of a turn.
+    ///
+    /// If the draw pile and discard pile have both run out, the rules' `ExhaustionPolicy` decides
+    /// what happens. Under `ExhaustionPolicy::EndRound` this returns `Error::DeckExhausted` and the
+    /// round is over, with nobody scoring.
+    pub fn draw(&mut self, location: DrawLocation) -> Result<Card> {
+        let next_phase = match self.phase {
+            Phase::AwaitingDraw => Phase::AwaitingDiscard,
+            Phase::LastTurns { drawn: false } => Phase::LastTurns { drawn: true },
+            _ => return Err(self.phase_error()),
+        };
         let card = match location {
             DrawLocation::DrawPile => self.next_card_from_deck(),
             DrawLocation::DiscardPile => self.next_card_from_discard_pile(),
         };
+        // Running out of cards ends the round under `ExhaustionPolicy::EndRound`, so that draw
+        // still counts as a move
+        if card.is_ok() || self.is_round_over() {
+            self.push_move(Move::Draw(location));
+        }
+        let card = card?;
         self.cur_player_mut().hand.push(card);
-        card
+        self.phase = next_phase;
+        self.emit(GameEvent::Drew {
+            player: self.current_player_index,
+            location,
+            card,
+        });
+        Ok(card)
+    }
+
+    pub fn is_round_over(&self) -> bool {
+        self.phase == Phase::RoundOver
+    }
+
+    pub fn is_game_over(&self) -> bool {
+        self.phase == Phase::GameOver
+    }
+
+    /// Finish the current round and deal the next one from a freshly shuffled deck. The wild rank
+    /// moves up by one and the deal passes to the left. Returns `false` if the round that just
+    /// finished was the last (King) round, in which case the game is over and nothing is dealt.
+    pub fn next_round(&mut self) -> Result<bool> {
+        if self.phase != Phase::RoundOver {
+            return Err(self.phase_error());
+        }
+        self.push_move(Move::NextRound);
+        match self.state.next() {
+            Some(state) => {
+                self.state = state;
+                self.dealer_index = self.next_seat(self.dealer_index);
+                self.start_round();
+                Ok(true)
+            }
+            None => {
+                self.phase = Phase::GameOver;
+                let result = self.result().expect("Game over without a result");
+                self.emit(GameEvent::GameOver(result));
+                Ok(false)
+            }
+        }
     }
 
+    /// Discard a card without going out. Equivalent to `turn(Action::Discard(card))`.
     pub fn discard(&mut self, card: Card) -> Result<()> {
-        let index = self
-            .cur_player()
-            .hand
-            .iter()
-            .position(|c| card == *c)
-            .ok_or(Error)?;
-        let taken = self.cur_player_mut().hand.remove(index);
-        self.discard_pile.push(taken);
-        Ok(())
+        self.turn(Action::Discard(card))
+    }
+
+    /// The game as seen from `seat`: public information plus that seat's own hand. Returns `None`
+    /// if there is no such seat.
+    pub fn view(&self, seat: usize) -> Option<PlayerView> {
+        let player = self.players.get(seat)?;
+        Some(PlayerView {
+            seat,
+            hand: Hand::new(player.hand.clone()),
+            game_state: self.state,
+            phase: self.phase,
+            dealer: self.dealer_index,
+            current_player: self.current_player_index,
+            first_out: self.first_player_gone_out_index,
+            discard_top: self.discard_pile.last().cloned(),
+            draw_pile_size: self.deck.len(),
+            draw_pile_forbidden: self.deck.is_empty()
+                && self.discard_pile.len() <= 1
+                && self.rules().exhaustion == ExhaustionPolicy::ForceDiscardDraw,
+            players: self
+                .players
+                .iter()
+                .map(|player| PublicPlayerInfo {
+                    score: player.score,
+                    hand_size: player.hand.len(),
+                })
+                .collect(),
+        })
+    }
+
+    pub fn players(&self) -> &[PlayerInfo] {
+        &self.players
+    }
+
+    pub fn dealer_index(&self) -> usize {
+        self.dealer_index
+    }
+
+    /// The seat that leads the current round, to the dealer's left.
+    pub fn first_player_index(&self) -> usize {
+        self.next_seat(self.dealer_index)
+    }
+
+    pub fn current_player_index(&self) -> usize {
+        self.current_player_index
     }
 
     pub fn cur_player(&self) -> &PlayerInfo {
@@ -96,53 +415,289 @@ impl Game {
         &mut self.players[self.current_player_index]
     }
 
-    // pub fn turn(&mut self, discard: Card, going_out: Option<Vec<ScoreGroup>>) {
-    pub fn turn(&mut self, action: Action) {
+    /// Finish the current player's turn by discarding and, optionally, going out. Only allowed
+    /// after drawing and before anyone has gone out.
+    pub fn turn(&mut self, action: Action) -> Result<()> {
+        if self.phase != Phase::AwaitingDiscard {
+            return Err(self.phase_error());
+        }
         let discard = action.discard();
-        self.discard_pile.push(discard);
-        let hand = &mut self.cur_player_mut().hand;
-        let removed_index = hand.iter().position(|card| card == &discard);
-        assert!(removed_index.is_some());
-        hand.remove(removed_index.unwrap());
-        if let Action::GoOut(_, score_groups) = action {
+        let removed_index = self.hand_index(discard)?;
+        if let Action::GoOut(_, score_groups) = &action {
+            let mut hand = self.cur_player().hand.clone();
+            hand.remove(removed_index);
+            validate_melds(&hand, score_groups, &[], &self.state).map_err(Error::InvalidMeld)?;
             self.first_player_gone_out_index = Some(self.current_player_index);
-            // Add score
-            // If first player out, it needs to include all cards
-            // If not first player out, there can be remaining cards
+            self.round_melds[self.current_player_index] = Some(PlayerRoundResult {
+                melds: score_groups.clone(),
+                leftover: Vec::new(),
+                points: Score::new(),
+            });
         }
+        self.push_move(Move::Turn(action.clone()));
+        self.cur_player_mut().hand.remove(removed_index);
+        self.discard_pile.push(discard);
+        let player = self.current_player_index;
+        self.emit(GameEvent::Discarded {
+            player,
+            card: discard,
+        });
+        self.phase = match action {
+            Action::Discard(_) => Phase::AwaitingDraw,
+            Action::GoOut(_, melds) => {
+                self.emit(GameEvent::WentOut { player, melds });
+                Phase::LastTurns { drawn: false }
+            }
+        };
+        self.end_turn();
+        Ok(())
     }
 
-    pub fn last_turn(&mut self, action: EndOfRoundAction) {
+    /// Finish the current player's last turn after someone else has gone out. Only allowed after
+    /// drawing. The player scores points for every card that isn't in one of their groups.
+    pub fn last_turn(&mut self, action: EndOfRoundAction) -> Result<()> {
+        if self.phase != (Phase::LastTurns { drawn: true }) {
+            return Err(self.phase_error());
+        }
         let discard = action.discard;
+        let index = self.hand_index(discard)?;
+        let mut hand = self.cur_player().hand.clone();
+        hand.remove(index);
+        validate_melds(&hand, &action.go_out, &action.remaining, &self.state)
+            .map_err(Error::InvalidMeld)?;
+        self.push_move(Move::LastTurn(action.clone()));
+        self.cur_player_mut().hand.remove(index);
         self.discard_pile.push(discard);
-        let points = action.remaining.iter().map(|c| c.score()).sum::<Score>();
+        let points = action
+            .remaining
+            .iter()
+            .map(|c| c.score(&self.state))
+            .sum::<Score>();
         self.players[self.current_player_index].score += points;
+        let player = self.current_player_index;
+        self.round_melds[player] = Some(PlayerRoundResult {
+            melds: action.go_out.clone(),
+            leftover: action.remaining.clone(),
+            points,
+        });
+        self.emit(GameEvent::Discarded {
+            player,
+            card: discard,
+        });
+        self.emit(GameEvent::LastTurn {
+            player,
+            melds: action.go_out,
+            leftover: action.remaining,
+            points,
+        });
+        self.phase = Phase::LastTurns { drawn: false };
+        self.end_turn();
+        Ok(())
+    }
+
+    /// Make `mv` as if by calling the matching method. A draw that fails with
+    /// `Error::DeckExhausted` but ends the round still counts as a move.
+    pub fn apply(&mut self, mv: &Move) -> Result<()> {
+        match mv {
+            Move::Draw(location) => match self.draw(*location) {
+                Ok(_) | Err(Error::DeckExhausted) => Ok(()),
+                Err(error) => Err(error),
+            },
+            Move::Turn(action) => self.turn(action.clone()),
+            Move::LastTurn(action) => self.last_turn(action.clone()),
+            Move::NextRound => self.next_round().map(|_| ()),
+        }
+    }
+
+    /// Take back the last move, putting every card back where it was. Observers are sent
+    /// `GameEvent::Undone`.
+    pub fn undo(&mut self) -> Result<()> {
+        let mv = self.moves.pop().ok_or(Error::NothingToUndo)?;
+        // Shuffles only depend on the seed and how many came before, so replaying the other moves
+        // from the start rebuilds the hidden state exactly, including any reshuffles. Only moves
+        // that succeeded are recorded, and `from_snapshot` only accepts moves that replay, so
+        // replaying them can't fail.
+        let mut game = self
+            .record()
+            .replay()
+            .expect("Recorded moves always replay");
+        std::mem::swap(&mut game.observers, &mut self.observers);
+        std::mem::swap(&mut game.undone_moves, &mut self.undone_moves);
+        *self = game;
+        for observer in self.observers.iter_mut() {
+            observer.on_event(&GameEvent::Undone(mv.clone()));
+        }
+        self.undone_moves.push(mv);
+        Ok(())
+    }
+
+    /// Make the last move that was undone again. Making any other move means there is nothing left
+    /// to redo.
+    pub fn redo(&mut self) -> Result<()> {
+        let mv = self.undone_moves.pop().ok_or(Error::NothingToRedo)?;
+        let undone_moves = std::mem::take(&mut self.undone_moves);
+        let result = self.apply(&mv);
+        self.undone_moves = undone_moves;
+        result
+    }
+
+    fn push_move(&mut self, mv: Move) {
+        self.moves.push(mv);
+        self.undone_moves.clear();
+    }
+
+    fn phase_error(&self) -> Error {
+        match self.phase {
+            Phase::GameOver => Error::GameOver,
+            _ => Error::WrongPhase,
+        }
+    }
+
+    fn hand_index(&self, card: Card) -> Result<usize> {
+        self.cur_player()
+            .hand
+            .iter()
+            .position(|c| card == *c)
+            .ok_or(Error::CardNotInHand(card))
+    }
+
+    /// Move play to the next player. Once play returns to the player who went out, the round is
+    /// over.
+    fn end_turn(&mut self) {
+        self.current_player_index = self.next_seat(self.current_player_index);
+        if self.first_player_gone_out_index == Some(self.current_player_index) {
+            self.phase = Phase::RoundOver;
+            self.finish_round();
+        }
+    }
+
+    /// The seat to the left of `seat`, which plays after it.
+    fn next_seat(&self, seat: usize) -> usize {
+        (seat + 1) % self.players.len()
+    }
+
+    /// Record the result of the round that just ended. Anybody who didn't get to go out or take
+    /// their last turn keeps their cards and scores nothing.
+    fn finish_round(&mut self) {
+        let round_melds = std::mem::replace(&mut self.round_melds, vec![None; self.players.len()]);
+        let players = self
+            .players
+            .iter()
+            .zip(round_melds)
+            .map(|(player, result)| {
+                result.unwrap_or_else(|| PlayerRoundResult {
+                    melds: Vec::new(),
+                    leftover: player.hand.clone(),
+                    points: Score::new(),
+                })
+            })
+            .collect();
+        let result = RoundResult {
+            round_rank: self.state.round_rank(),
+            dealer: self.dealer_index,
+            first_out: self.first_player_gone_out_index,
+            players,
+        };
+        self.round_results.push(result.clone());
+        self.emit(GameEvent::RoundScored(result));
+    }
+
+    /// Record `event` and pass it to every observer.
+    fn emit(&mut self, event: GameEvent) {
+        for observer in self.observers.iter_mut() {
+            observer.on_event(&event);
+        }
+        self.events.push(event);
     }
 
     /// Return the next card from the deck. If the deck is empty, the discard pile (minus the top
     /// card) is shuffled to become the new deck.
-    fn next_card_from_deck(&mut self) -> Card {
+    fn next_card_from_deck(&mut self) -> Result<Card> {
         if let Some(card) = self.deck.pop() {
-            return card;
+            return Ok(card);
+        }
+        if self.discard_pile.len() <= 1 {
+            return self.deck_exhausted();
         }
         let mut bottom_discarded_cards = self
             .discard_pile
             .drain(0..self.discard_pile.len() - 1)
             .collect::<Vec<_>>();
-        let mut rng = thread_rng();
-        bottom_discarded_cards.shuffle(&mut rng);
+        bottom_discarded_cards.shuffle(&mut self.next_rng());
+        self.emit(GameEvent::Reshuffled {
+            num_cards: bottom_discarded_cards.len(),
+        });
         self.deck = bottom_discarded_cards;
-        self.deck
-            .pop()
-            .expect("Empty deck and less than one discarded card")
+        self.deck.pop().ok_or(Error::DeckExhausted)
+    }
+
+    /// Apply the exhaustion policy when neither the deck nor the discard pile can supply a card.
+    fn deck_exhausted(&mut self) -> Result<Card> {
+        let policy = self.rules().exhaustion;
+        self.emit(GameEvent::DeckExhausted { policy });
+        match policy {
+            ExhaustionPolicy::EndRound => {
+                // Nobody scores, including anyone who already had their last turn
+                for (player, result) in self.players.iter_mut().zip(self.round_melds.iter_mut()) {
+                    if let Some(result) = result {
+                        player.score = Score(player.score.value() - result.points.value());
+                        result.points = Score::new();
+                    }
+                }
+                self.phase = Phase::RoundOver;
+                self.finish_round();
+                Err(Error::DeckExhausted)
+            }
+            ExhaustionPolicy::FreshDeck => {
+                self.deck = self.shuffled_deck();
+                self.deck.pop().ok_or(Error::DeckExhausted)
+            }
+            ExhaustionPolicy::ForceDiscardDraw => Err(Error::DeckExhausted),
+        }
+    }
+
+    fn shuffled_deck(&mut self) -> Vec<Card> {
+        let mut deck = full_deck(&self.rules().deck).collect::<Vec<_>>();
+        deck.shuffle(&mut self.next_rng());
+        deck
     }
 
-    fn next_card_from_discard_pile(&mut self) -> Card {
-        self.discard_pile.pop().expect("Empty discard pile")
+    fn next_card_from_discard_pile(&mut self) -> Result<Card> {
+        self.discard_pile.pop().ok_or(Error::EmptyDiscardPile)
+    }
+
+    /// The RNG for the next shuffle.
+    fn next_rng(&mut self) -> StdRng {
+        let mut seed = <StdRng as SeedableRng>::Seed::default();
+        seed[..8].copy_from_slice(&self.seed.to_le_bytes());
+        seed[8..16].copy_from_slice(&self.num_shuffles.to_le_bytes());
+        self.num_shuffles += 1;
+        StdRng::from_seed(seed)
+    }
+
+    /// Shuffle a full deck, deal a hand to each player, and start the discard pile.
+    fn start_round(&mut self) {
+        self.current_player_index = self.first_player_index();
+        self.first_player_gone_out_index = None;
+        self.phase = Phase::AwaitingDraw;
+        for player in self.players.iter_mut() {
+            player.hand.clear();
+        }
+        self.round_melds = vec![None; self.players.len()];
+        self.discard_pile.clear();
+        self.deck = self.shuffled_deck();
+        self.deal();
+        self.discard_pile
+            .push(self.deck.pop().expect("Empty deck after dealing"));
+        self.emit(GameEvent::RoundStarted {
+            round_rank: self.state.round_rank(),
+            dealer: self.dealer_index,
+            first_player: self.current_player_index,
+        });
     }
 
     fn deal(&mut self) {
-        let num_cards = self.state.wild_rank().number();
+        let num_cards = self.state.num_cards();
         for player in self.players.iter_mut() {
             let index = self.deck.len() - num_cards;
             player.hand.extend(self.deck.drain(index..));
@@ -151,7 +706,7 @@ impl Game {
 
     pub fn debug_strings(&self) -> Vec<String> {
         let mut strings = Vec::new();
-        strings.push(format!("Round: {} cards", self.state.wild_rank().number()));
+        strings.push(format!("Round: {} cards", self.state.num_cards()));
         strings.push("PlayerInfos:".to_string());
         for player in self.players.iter() {
             strings.push(player.debug_string());
@@ -198,6 +753,45 @@ impl PlayerInfo {
     }
 }
 
+/// Whatever the rules say, a game needs at least two players to pass the turn between.
+const MIN_PLAYERS: usize = 2;
+
+fn check_player_count(num_players: usize, rules: &RuleSet) -> Result<()> {
+    if num_players < rules.min_players.max(MIN_PLAYERS) || num_players > rules.max_players {
+        return Err(Error::InvalidPlayerCount(num_players));
+    }
+    Ok(())
+}
+
+/// Check that `score_groups` and `remaining` together use exactly the cards in `hand` and that
+/// every group is valid for `game_state`.
+fn validate_melds(
+    hand: &[Card],
+    score_groups: &[ScoreGroup],
+    remaining: &[Card],
+    game_state: &GameState,
+) -> std::result::Result<(), MeldError> {
+    let mut hand = CardMultiset::from(hand);
+    let grouped = score_groups
+        .iter()
+        .flat_map(|group| group.cards())
+        .chain(remaining);
+    for &card in grouped {
+        if !hand.remove(card) {
+            return Err(MeldError::CardsDoNotMatchHand);
+        }
+    }
+    if !hand.is_empty() {
+        return Err(MeldError::CardsDoNotMatchHand);
+    }
+    for group in score_groups {
+        group
+            .validate(game_state)
+            .map_err(MeldError::InvalidGroup)?;
+    }
+    Ok(())
+}
+
 fn pretty_cards(cards: &[Card]) -> String {
     cards
         .iter()
@@ -205,3 +799,864 @@ fn pretty_cards(cards: &[Card]) -> String {
         .collect::<Vec<_>>()
         .join(",")
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::hand::Hand;
+    use crate::score_group::{Run, Set};
+    use crate::test_utils::{go_out_with_jokers, play_round_going_out};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    fn cards(string: &str) -> Vec<Card> {
+        Hand::try_from(string).unwrap().cards
+    }
+
+    fn total_cards(game: &Game) -> usize {
+        game.deck.len()
+            + game.discard_pile.len()
+            + game.players.iter().map(|p| p.hand.len()).sum::<usize>()
+    }
+
+    #[test]
+    fn test_new_deals_first_round() {
+        let game = Game::new(3).unwrap();
+        assert_eq!(game.state.round_rank(), Rank::Three);
+        assert!(game.players.iter().all(|p| p.hand.len() == 3));
+        assert_eq!(game.discard_pile.len(), 1);
+        assert_eq!(total_cards(&game), DeckSpec::official().num_cards());
+    }
+
+    #[test]
+    fn test_same_seed_same_game() {
+        let mut game1 = Game::with_seed(4, 1234).unwrap();
+        let mut game2 = Game::with_seed(4, 1234).unwrap();
+        assert_eq!(game1.seed(), 1234);
+        // Enough turns to go through the draw pile and reshuffle the discard pile
+        for _ in 0..150 {
+            let card1 = game1.draw(DrawLocation::DrawPile).unwrap();
+            let card2 = game2.draw(DrawLocation::DrawPile).unwrap();
+            assert_eq!(card1, card2);
+            let discard = game1.cur_player().hand[0];
+            game1.discard(discard).unwrap();
+            game2.discard(discard).unwrap();
+        }
+        assert!(game1.num_shuffles > 1);
+        assert_eq!(game1.deck, game2.deck);
+        assert_eq!(game1.discard_pile, game2.discard_pile);
+    }
+
+    #[test]
+    fn test_same_seed_same_deal() {
+        let mut game1 = Game::with_deck_spec(3, DeckSpec::new(0, 100)).unwrap();
+        let seed = game1.seed();
+        let rules = *game1.rules();
+        let mut game2 = Game::with_rules_and_seed(3, rules, seed).unwrap();
+        play_round_going_out(&mut game1);
+        play_round_going_out(&mut game2);
+        game1.next_round().unwrap();
+        game2.next_round().unwrap();
+        assert_eq!(game1.deck, game2.deck);
+
+        let game1 = Game::with_seed(3, 1).unwrap();
+        let game2 = Game::with_seed(3, 1).unwrap();
+        let game3 = Game::with_seed(3, 2).unwrap();
+        assert_eq!(game1.deck, game2.deck);
+        assert_ne!(game1.deck, game3.deck);
+        for (player1, player2) in game1.players.iter().zip(game2.players.iter()) {
+            assert_eq!(player1.hand, player2.hand);
+        }
+    }
+
+    #[test]
+    fn test_player_count() {
+        assert_eq!(Game::new(0).err(), Some(Error::InvalidPlayerCount(0)));
+        assert_eq!(Game::new(1).err(), Some(Error::InvalidPlayerCount(1)));
+        assert!(Game::new(2).is_ok());
+        assert!(Game::new(7).is_ok());
+        assert_eq!(Game::new(8).err(), Some(Error::InvalidPlayerCount(8)));
+
+        let rules = RuleSet {
+            min_players: 0,
+            ..RuleSet::official()
+        };
+        assert_eq!(
+            Game::with_rules(0, rules).err(),
+            Some(Error::InvalidPlayerCount(0))
+        );
+        assert_eq!(
+            Game::with_rules(1, rules).err(),
+            Some(Error::InvalidPlayerCount(1))
+        );
+    }
+
+    #[test]
+    fn test_not_enough_cards() {
+        let rules = RuleSet {
+            deck: DeckSpec::new(1, 3),
+            ..RuleSet::official()
+        };
+        assert!(Game::with_rules(4, rules).is_ok());
+        assert_eq!(
+            Game::with_rules(5, rules).err(),
+            Some(Error::NotEnoughCards {
+                needed: 66,
+                available: 58
+            })
+        );
+        let rules = RuleSet {
+            max_players: 20,
+            ..RuleSet::official()
+        };
+        assert!(Game::with_rules(8, rules).is_ok());
+        assert_eq!(
+            Game::with_rules(9, rules).err(),
+            Some(Error::NotEnoughCards {
+                needed: 118,
+                available: 116
+            })
+        );
+    }
+
+    #[test]
+    fn test_view() {
+        let mut game = Game::with_seed(3, 7).unwrap();
+        let card = game.draw(DrawLocation::DrawPile).unwrap();
+        game.discard(card).unwrap();
+        game.players[2].score = Score(12);
+        let view = game.view(1).unwrap();
+        assert_eq!(view.seat, 1);
+        assert_eq!(view.hand.cards, game.players[1].hand);
+        assert_eq!(view.game_state, game.state());
+        assert_eq!(view.phase, Phase::AwaitingDraw);
+        assert_eq!(view.dealer, 2);
+        assert_eq!(view.current_player, 1);
+        assert!(view.is_my_turn());
+        assert_eq!(view.first_out, None);
+        assert_eq!(view.discard_top, Some(card));
+        assert_eq!(view.draw_pile_size, game.deck.len());
+        assert!(!view.draw_pile_forbidden);
+        assert_eq!(
+            view.players,
+            vec![
+                PublicPlayerInfo {
+                    score: Score(0),
+                    hand_size: 3,
+                },
+                PublicPlayerInfo {
+                    score: Score(0),
+                    hand_size: 3,
+                },
+                PublicPlayerInfo {
+                    score: Score(12),
+                    hand_size: 3,
+                },
+            ]
+        );
+        assert!(!game.view(0).unwrap().is_my_turn());
+        assert_eq!(game.view(3), None);
+    }
+
+    #[test]
+    fn test_dealer_rotation() {
+        let mut game = Game::with_deck_spec(3, DeckSpec::new(0, 100)).unwrap();
+        let mut dealers = Vec::new();
+        loop {
+            dealers.push((game.dealer_index(), game.current_player_index()));
+            assert_eq!(game.first_player_index(), game.current_player_index());
+            play_round_going_out(&mut game);
+            if !game.next_round().unwrap() {
+                break;
+            }
+        }
+        assert_eq!(&dealers[..4], &[(2, 0), (0, 1), (1, 2), (2, 0)]);
+        let results = game.round_results();
+        assert_eq!(results[1].dealer, 0);
+        // Everybody goes out on their first turn
+        assert_eq!(results[1].first_out, Some(1));
+        let round_starts = game
+            .events()
+            .iter()
+            .filter_map(|event| match event {
+                GameEvent::RoundStarted {
+                    round_rank,
+                    dealer,
+                    first_player,
+                } => Some((*round_rank, *dealer, *first_player)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(round_starts.len(), 11);
+        assert_eq!(round_starts[1], (Rank::Four, 0, 1));
+        assert_eq!(round_starts[10], (Rank::King, 0, 1));
+    }
+
+    #[test]
+    fn test_seven_players_king_round() {
+        let mut game = Game::new(7).unwrap();
+        game.state = GameState::new(Rank::King);
+        game.start_round();
+        assert!(game.players.iter().all(|p| p.hand.len() == 13));
+        assert_eq!(game.discard_pile.len(), 1);
+        assert_eq!(game.deck.len(), 116 - 7 * 13 - 1);
+    }
+
+    #[test]
+    fn test_next_round() {
+        let mut game = Game::with_deck_spec(2, DeckSpec::new(0, 100)).unwrap();
+        play_round_going_out(&mut game);
+        assert_eq!(game.next_round(), Ok(true));
+        assert_eq!(game.state.round_rank(), Rank::Four);
+        assert_eq!(game.phase(), Phase::AwaitingDraw);
+        assert!(game.players.iter().all(|p| p.hand.len() == 4));
+        assert_eq!(game.discard_pile.len(), 1);
+        assert_eq!(total_cards(&game), 100);
+        assert!(!game.is_game_over());
+        assert_eq!(game.result(), None);
+    }
+
+    #[test]
+    fn test_full_game() {
+        let mut game = Game::with_deck_spec(2, DeckSpec::new(0, 100)).unwrap();
+        let mut rounds = 1;
+        play_round_going_out(&mut game);
+        while game.next_round().unwrap() {
+            play_round_going_out(&mut game);
+            rounds += 1;
+            assert_eq!(game.cur_player().hand.len(), game.state.num_cards());
+        }
+        assert_eq!(rounds, 11);
+        assert_eq!(game.state.round_rank(), Rank::King);
+        assert!(game.is_game_over());
+        assert_eq!(game.phase(), Phase::GameOver);
+        let result = game.result().unwrap();
+        assert_eq!(result.standings.len(), 2);
+        // The lead alternates and the leader always goes out first
+        assert_eq!(result.standings[0].rounds_won, 6);
+        assert_eq!(result.standings[1].rounds_won, 5);
+        assert_eq!(result.winners().collect::<Vec<_>>(), vec![0, 1]);
+        assert_eq!(game.next_round(), Err(Error::GameOver));
+        assert_eq!(game.draw(DrawLocation::DrawPile), Err(Error::GameOver));
+    }
+
+    #[test]
+    fn test_round_events() {
+        let mut game = Game::with_deck_spec(2, DeckSpec::new(0, 100)).unwrap();
+        play_round_going_out(&mut game);
+        let melds = vec![ScoreGroup::Set(
+            Set::try_from(&cards("Joker,Joker,Joker"), &game.state).unwrap(),
+        )];
+        assert_eq!(
+            game.events(),
+            &[
+                GameEvent::RoundStarted {
+                    round_rank: Rank::Three,
+                    dealer: 1,
+                    first_player: 0,
+                },
+                GameEvent::Drew {
+                    player: 0,
+                    location: DrawLocation::DrawPile,
+                    card: Card::Joker,
+                },
+                GameEvent::Discarded {
+                    player: 0,
+                    card: Card::Joker,
+                },
+                GameEvent::WentOut {
+                    player: 0,
+                    melds: melds.clone(),
+                },
+                GameEvent::Drew {
+                    player: 1,
+                    location: DrawLocation::DrawPile,
+                    card: Card::Joker,
+                },
+                GameEvent::Discarded {
+                    player: 1,
+                    card: Card::Joker,
+                },
+                GameEvent::LastTurn {
+                    player: 1,
+                    melds,
+                    leftover: Vec::new(),
+                    points: Score(0),
+                },
+                GameEvent::RoundScored(game.round_results()[0].clone()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_observers() {
+        let mut game = Game::with_deck_spec(2, DeckSpec::new(0, 100)).unwrap();
+        play_round_going_out(&mut game);
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let observer_seen = Rc::clone(&seen);
+        game.add_observer(Box::new(move |event: &GameEvent| {
+            observer_seen.borrow_mut().push(event.clone())
+        }));
+        // Events from before the observer was added are sent to it straight away
+        assert_eq!(seen.borrow().as_slice(), game.events());
+        while game.next_round().unwrap() {
+            play_round_going_out(&mut game);
+        }
+        assert_eq!(seen.borrow().as_slice(), game.events());
+        assert_eq!(
+            seen.borrow().last(),
+            Some(&GameEvent::GameOver(game.result().unwrap()))
+        );
+    }
+
+    /// Everything about the position, hidden or not.
+    fn snapshot(game: &Game) -> (Vec<PlayerView>, Vec<Card>, Vec<Card>, Vec<GameEvent>, u64) {
+        let views = (0..game.players.len()).map(|seat| game.view(seat).unwrap());
+        (
+            views.collect(),
+            game.deck.clone(),
+            game.discard_pile.clone(),
+            game.events.clone(),
+            game.num_shuffles,
+        )
+    }
+
+    #[test]
+    fn test_undo_redo() {
+        let mut game = Game::with_seed(2, 5).unwrap();
+        let start = snapshot(&game);
+        game.draw(DrawLocation::DrawPile).unwrap();
+        let drawn = snapshot(&game);
+        game.discard(game.cur_player().hand[0]).unwrap();
+        let discarded = snapshot(&game);
+
+        game.undo().unwrap();
+        assert_eq!(snapshot(&game), drawn);
+        game.undo().unwrap();
+        assert_eq!(snapshot(&game), start);
+        assert_eq!(game.undo(), Err(Error::NothingToUndo));
+
+        game.redo().unwrap();
+        assert_eq!(snapshot(&game), drawn);
+        game.redo().unwrap();
+        assert_eq!(snapshot(&game), discarded);
+        assert_eq!(game.redo(), Err(Error::NothingToRedo));
+    }
+
+    #[test]
+    fn test_new_move_clears_redo() {
+        let mut game = Game::with_seed(2, 5).unwrap();
+        game.draw(DrawLocation::DrawPile).unwrap();
+        game.undo().unwrap();
+        game.draw(DrawLocation::DiscardPile).unwrap();
+        assert_eq!(game.redo(), Err(Error::NothingToRedo));
+    }
+
+    #[test]
+    fn test_undo_reshuffle() {
+        let mut game = Game::with_seed(2, 8).unwrap();
+        while !game
+            .events()
+            .iter()
+            .any(|event| matches!(event, GameEvent::Reshuffled { .. }))
+        {
+            game.draw(DrawLocation::DrawPile).unwrap();
+            game.discard(game.cur_player().hand[0]).unwrap();
+        }
+        let reshuffled = snapshot(&game);
+        game.undo().unwrap();
+        game.undo().unwrap();
+        // The discard pile is back, and the draw pile is empty again
+        assert!(game.deck.is_empty());
+        assert!(game.discard_pile.len() > 1);
+        game.redo().unwrap();
+        game.redo().unwrap();
+        assert_eq!(snapshot(&game), reshuffled);
+    }
+
+    #[test]
+    fn test_undo_go_out_and_next_round() {
+        let mut game = Game::with_deck_spec(2, DeckSpec::new(0, 100)).unwrap();
+        play_round_going_out(&mut game);
+        let round_over = snapshot(&game);
+        game.next_round().unwrap();
+        let next_round = snapshot(&game);
+        game.undo().unwrap();
+        assert!(game.is_round_over());
+        assert_eq!(snapshot(&game), round_over);
+        // Undo both players' turns
+        for _ in 0..4 {
+            game.undo().unwrap();
+        }
+        assert_eq!(game.phase(), Phase::AwaitingDraw);
+        assert_eq!(game.first_player_gone_out_index, None);
+        assert!(game.round_results().is_empty());
+        for _ in 0..5 {
+            game.redo().unwrap();
+        }
+        assert_eq!(snapshot(&game), next_round);
+    }
+
+    #[test]
+    fn test_undo_notifies_observers() {
+        let mut game = Game::with_seed(2, 5).unwrap();
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let observer_seen = Rc::clone(&seen);
+        game.add_observer(Box::new(move |event: &GameEvent| {
+            observer_seen.borrow_mut().push(event.clone())
+        }));
+        game.draw(DrawLocation::DiscardPile).unwrap();
+        game.undo().unwrap();
+        assert_eq!(
+            seen.borrow().last(),
+            Some(&GameEvent::Undone(Move::Draw(DrawLocation::DiscardPile)))
+        );
+        // Observers are kept
+        game.redo().unwrap();
+        assert!(matches!(seen.borrow().last(), Some(GameEvent::Drew { .. })));
+    }
+
+    #[test]
+    fn test_round_over_after_last_turns() {
+        let mut game = Game::with_deck_spec(3, DeckSpec::new(0, 100)).unwrap();
+        game.draw(DrawLocation::DrawPile).unwrap();
+        let (discard, go_out) = go_out_with_jokers(&game.cur_player().hand, &game.state);
+        game.turn(Action::GoOut(discard, go_out)).unwrap();
+        assert_eq!(game.phase(), Phase::LastTurns { drawn: false });
+        for _ in 0..2 {
+            let remaining = game.cur_player().hand.clone();
+            let card = game.draw(DrawLocation::DrawPile).unwrap();
+            game.last_turn(EndOfRoundAction {
+                discard: card,
+                go_out: Vec::new(),
+                remaining,
+            })
+            .unwrap();
+        }
+        assert!(game.is_round_over());
+    }
+
+    #[test]
+    fn test_validate_melds() {
+        let state = GameState::new(Rank::Five);
+        let run = Run::try_from(&cards("3S,4S,5H"), &state).unwrap();
+        let set = Set::try_from(&cards("KD,KH,Joker"), &state).unwrap();
+        let groups = vec![ScoreGroup::Run(run), ScoreGroup::Set(set)];
+        assert_eq!(
+            validate_melds(&cards("Joker,KH,5H,3S,KD,4S"), &groups, &[], &state),
+            Ok(())
+        );
+        // Card left over
+        assert_eq!(
+            validate_melds(&cards("Joker,KH,5H,3S,KD,4S,7C"), &groups, &[], &state),
+            Err(MeldError::CardsDoNotMatchHand)
+        );
+        // Card not in hand
+        assert_eq!(
+            validate_melds(&cards("Joker,KH,5H,3S,KD"), &groups, &[], &state),
+            Err(MeldError::CardsDoNotMatchHand)
+        );
+        // Groups built for another round
+        assert_eq!(
+            validate_melds(
+                &cards("Joker,KH,5H,3S,KD,4S"),
+                &groups,
+                &[],
+                &GameState::new(Rank::Six)
+            ),
+            Err(MeldError::InvalidGroup(score_group::Error::NotAllSameSuit))
+        );
+    }
+
+    #[test]
+    fn test_validate_go_out_duplicates() {
+        let state = GameState::new(Rank::Four);
+        let set = Set::try_from(&cards("5S,5S,5S"), &state).unwrap();
+        let run = Run::try_from(&cards("8D,9D,10D"), &state).unwrap();
+        let groups = vec![ScoreGroup::Set(set), ScoreGroup::Run(run)];
+        assert_eq!(
+            validate_melds(&cards("5S,8D,5S,9D,5S,10D"), &groups, &[], &state),
+            Ok(())
+        );
+        assert_eq!(
+            validate_melds(&cards("5S,8D,5S,9D,5H,10D"), &groups, &[], &state),
+            Err(MeldError::CardsDoNotMatchHand)
+        );
+        assert_eq!(
+            validate_melds(&cards("5S,8D,5S,9D,5S,5S,10D"), &groups, &[], &state),
+            Err(MeldError::CardsDoNotMatchHand)
+        );
+    }
+
+    #[test]
+    fn test_validate_melds_remaining() {
+        let state = GameState::new(Rank::Five);
+        let set = Set::try_from(&cards("KD,KH,Joker"), &state).unwrap();
+        let groups = vec![ScoreGroup::Set(set)];
+        assert_eq!(
+            validate_melds(
+                &cards("3S,KD,KH,Joker,7C"),
+                &groups,
+                &cards("7C,3S"),
+                &state
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            validate_melds(&cards("3S,KD,KH,Joker,7C"), &groups, &cards("7C"), &state),
+            Err(MeldError::CardsDoNotMatchHand)
+        );
+        assert_eq!(
+            validate_melds(&cards("3S,7C"), &[], &cards("7C,3S"), &state),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_round_results() {
+        let mut game = Game::with_deck_spec(3, DeckSpec::new(0, 100)).unwrap();
+        // First player goes out
+        game.draw(DrawLocation::DrawPile).unwrap();
+        let (discard, go_out) = go_out_with_jokers(&game.cur_player().hand, &game.state);
+        game.turn(Action::GoOut(discard, go_out.clone())).unwrap();
+        // Second player keeps everything
+        game.draw(DrawLocation::DrawPile).unwrap();
+        let remaining = game.cur_player().hand[1..].to_vec();
+        game.last_turn(EndOfRoundAction {
+            discard,
+            go_out: Vec::new(),
+            remaining: remaining.clone(),
+        })
+        .unwrap();
+        assert!(game.round_results().is_empty());
+        // Third player lies about what they have left
+        game.draw(DrawLocation::DrawPile).unwrap();
+        let result = game.last_turn(EndOfRoundAction {
+            discard,
+            go_out: Vec::new(),
+            remaining: Vec::new(),
+        });
+        assert_eq!(
+            result,
+            Err(Error::InvalidMeld(MeldError::CardsDoNotMatchHand))
+        );
+        game.last_turn(EndOfRoundAction {
+            discard,
+            go_out: go_out.clone(),
+            remaining: Vec::new(),
+        })
+        .unwrap();
+
+        assert!(game.is_round_over());
+        let results = game.round_results();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].round_rank, Rank::Three);
+        assert_eq!(results[0].first_out, Some(0));
+        assert_eq!(
+            results[0].players,
+            vec![
+                PlayerRoundResult {
+                    melds: go_out.clone(),
+                    leftover: Vec::new(),
+                    points: Score(0),
+                },
+                PlayerRoundResult {
+                    melds: Vec::new(),
+                    leftover: remaining,
+                    points: Score(150),
+                },
+                PlayerRoundResult {
+                    melds: go_out,
+                    leftover: Vec::new(),
+                    points: Score(0),
+                },
+            ]
+        );
+        assert_eq!(
+            results[0].points().collect::<Vec<_>>(),
+            vec![Score(0), Score(150), Score(0)]
+        );
+        assert_eq!(game.players[1].score, Score(150));
+    }
+
+    #[test]
+    fn test_round_results_natural_melds() {
+        let mut game = Game::with_seed(2, 5).unwrap();
+        // Rig the hands and the next two draws. Threes are wild in the first round.
+        let first = game.current_player_index;
+        let other = game.next_seat(first);
+        game.players[first].hand = cards("4S,5S,6S,7C,7D,KH");
+        game.players[other].hand = cards("8H,9H,Joker,JH,KS,KC,3D,7D,3C,Joker");
+        game.deck.push(Card::try_from("QS").unwrap());
+        game.deck.push(Card::try_from("7H").unwrap());
+
+        game.draw(DrawLocation::DrawPile).unwrap();
+        let first_melds = vec![
+            ScoreGroup::Run(Run::try_from(&cards("4S,5S,6S"), &game.state).unwrap()),
+            ScoreGroup::Set(Set::try_from(&cards("7C,7D,7H"), &game.state).unwrap()),
+        ];
+        game.turn(Action::GoOut(
+            Card::try_from("KH").unwrap(),
+            first_melds.clone(),
+        ))
+        .unwrap();
+
+        // Wilds in groups score nothing, but left in hand they score 20, or 50 for a joker
+        game.draw(DrawLocation::DrawPile).unwrap();
+        let other_melds = vec![
+            ScoreGroup::Run(Run::try_from(&cards("8H,9H,Joker,JH"), &game.state).unwrap()),
+            ScoreGroup::Set(Set::try_from(&cards("KS,KC,3D"), &game.state).unwrap()),
+        ];
+        let leftover = cards("7D,3C,Joker");
+        game.last_turn(EndOfRoundAction {
+            discard: Card::try_from("QS").unwrap(),
+            go_out: other_melds.clone(),
+            remaining: leftover.clone(),
+        })
+        .unwrap();
+
+        assert!(game.is_round_over());
+        let result = &game.round_results()[0];
+        assert_eq!(result.first_out, Some(first));
+        assert_eq!(
+            result.players[first],
+            PlayerRoundResult {
+                melds: first_melds,
+                leftover: Vec::new(),
+                points: Score(0),
+            }
+        );
+        assert_eq!(
+            leftover
+                .iter()
+                .map(|card| card.score(&game.state))
+                .collect::<Vec<_>>(),
+            vec![Score(7), Score(20), Score(50)]
+        );
+        assert_eq!(
+            result.players[other],
+            PlayerRoundResult {
+                melds: other_melds,
+                leftover,
+                points: Score(77),
+            }
+        );
+        assert_eq!(game.players[first].score, Score(0));
+        assert_eq!(game.players[other].score, Score(77));
+    }
+
+    #[test]
+    fn test_card_not_in_hand() {
+        let mut game = Game::with_deck_spec(2, DeckSpec::new(0, 100)).unwrap();
+        game.draw(DrawLocation::DrawPile).unwrap();
+        let card = Card::try_from("3S").unwrap();
+        assert_eq!(
+            game.turn(Action::Discard(card)),
+            Err(Error::CardNotInHand(card))
+        );
+        assert_eq!(game.discard(card), Err(Error::CardNotInHand(card)));
+    }
+
+    #[test]
+    fn test_wrong_phase() {
+        let mut game = Game::new(2).unwrap();
+        let card = game.cur_player().hand[0];
+        assert_eq!(game.turn(Action::Discard(card)), Err(Error::WrongPhase));
+        assert_eq!(game.next_round(), Err(Error::WrongPhase));
+        let card = game.draw(DrawLocation::DrawPile).unwrap();
+        assert_eq!(game.phase(), Phase::AwaitingDiscard);
+        assert_eq!(game.draw(DrawLocation::DrawPile), Err(Error::WrongPhase));
+        let action = EndOfRoundAction {
+            discard: card,
+            go_out: Vec::new(),
+            remaining: Vec::new(),
+        };
+        assert_eq!(game.last_turn(action), Err(Error::WrongPhase));
+    }
+
+    #[test]
+    fn test_turn_moves_to_next_player() {
+        let mut game = Game::new(3).unwrap();
+        let card = game.draw(DrawLocation::DrawPile).unwrap();
+        game.discard(card).unwrap();
+        assert_eq!(game.current_player_index(), 1);
+        assert_eq!(game.phase(), Phase::AwaitingDraw);
+        assert_eq!(game.discard_pile.last(), Some(&card));
+    }
+
+    #[test]
+    fn test_empty_discard_pile() {
+        let mut game = Game::new(2).unwrap();
+        game.draw(DrawLocation::DiscardPile).unwrap();
+        game.phase = Phase::AwaitingDraw;
+        assert_eq!(
+            game.draw(DrawLocation::DiscardPile),
+            Err(Error::EmptyDiscardPile)
+        );
+    }
+
+    fn exhausted_game(policy: ExhaustionPolicy) -> Game {
+        let rules = RuleSet {
+            deck: DeckSpec::new(0, 27),
+            exhaustion: policy,
+            ..RuleSet::official()
+        };
+        let mut game = Game::with_rules(2, rules).unwrap();
+        // Leave nothing to draw but the single card on the discard pile
+        game.deck.clear();
+        game
+    }
+
+    #[test]
+    fn test_deck_exhausted_end_round() {
+        let mut game = exhausted_game(ExhaustionPolicy::EndRound);
+        assert_eq!(game.draw(DrawLocation::DrawPile), Err(Error::DeckExhausted));
+        assert!(game.is_round_over());
+        assert_eq!(game.moves, vec![Move::Draw(DrawLocation::DrawPile)]);
+        assert!(game.players.iter().all(|p| p.score == Score(0)));
+        let results = game.round_results();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].first_out, None);
+        assert!(results[0].points().all(|points| points == Score(0)));
+        assert_eq!(results[0].players[1].leftover, game.players[1].hand);
+        let events = game.events();
+        assert_eq!(
+            &events[events.len() - 2..],
+            &[
+                GameEvent::DeckExhausted {
+                    policy: ExhaustionPolicy::EndRound
+                },
+                GameEvent::RoundScored(results[0].clone()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_deck_exhausted_during_last_turns() {
+        let rules = RuleSet {
+            deck: DeckSpec::new(0, 40),
+            exhaustion: ExhaustionPolicy::EndRound,
+            ..RuleSet::official()
+        };
+        let mut game = Game::with_rules(3, rules).unwrap();
+        game.draw(DrawLocation::DrawPile).unwrap();
+        let (discard, go_out) = go_out_with_jokers(&game.cur_player().hand, &game.state);
+        game.turn(Action::GoOut(discard, go_out)).unwrap();
+        // The next player keeps all three jokers
+        game.draw(DrawLocation::DrawPile).unwrap();
+        let remaining = game.cur_player().hand[1..].to_vec();
+        game.last_turn(EndOfRoundAction {
+            discard: Card::Joker,
+            go_out: Vec::new(),
+            remaining,
+        })
+        .unwrap();
+        assert_eq!(game.players[1].score, Score(150));
+
+        game.deck.clear();
+        game.discard_pile.truncate(1);
+        assert_eq!(game.draw(DrawLocation::DrawPile), Err(Error::DeckExhausted));
+        assert!(game.is_round_over());
+        assert!(game.players.iter().all(|p| p.score == Score(0)));
+        let result = &game.round_results()[0];
+        assert_eq!(result.first_out, Some(0));
+        assert!(result.points().all(|points| points == Score(0)));
+        assert_eq!(result.players[1].leftover, cards("Joker,Joker,Joker"));
+    }
+
+    #[test]
+    fn test_deck_exhausted_fresh_deck() {
+        let mut game = exhausted_game(ExhaustionPolicy::FreshDeck);
+        assert_eq!(game.draw(DrawLocation::DrawPile), Ok(Card::Joker));
+        assert_eq!(game.deck.len(), 26);
+        let events = game.events();
+        assert_eq!(
+            events[events.len() - 2],
+            GameEvent::DeckExhausted {
+                policy: ExhaustionPolicy::FreshDeck
+            }
+        );
+    }
+
+    #[test]
+    fn test_deck_exhausted_force_discard_draw() {
+        let mut game = exhausted_game(ExhaustionPolicy::ForceDiscardDraw);
+        assert_eq!(game.draw(DrawLocation::DrawPile), Err(Error::DeckExhausted));
+        assert_eq!(game.phase(), Phase::AwaitingDraw);
+        // Nothing happened, so there is nothing to record or undo
+        assert!(game.moves.is_empty());
+        assert_eq!(game.undo(), Err(Error::NothingToUndo));
+        assert!(game.view(0).unwrap().draw_pile_forbidden);
+        assert_eq!(
+            game.events().last(),
+            Some(&GameEvent::DeckExhausted {
+                policy: ExhaustionPolicy::ForceDiscardDraw
+            })
+        );
+        assert_eq!(game.draw(DrawLocation::DiscardPile), Ok(Card::Joker));
+    }
+
+    #[test]
+    fn test_turn_rejects_invalid_go_out() {
+        let mut game = Game::new(2).unwrap();
+        let card = game.draw(DrawLocation::DrawPile).unwrap();
+        let result = game.turn(Action::GoOut(card, Vec::new()));
+        assert_eq!(
+            result,
+            Err(Error::InvalidMeld(MeldError::CardsDoNotMatchHand))
+        );
+        assert_eq!(game.current_player_index, 0);
+        assert_eq!(game.cur_player().hand.len(), 4);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let state = GameState::new(Rank::Five);
+        let set = Set::try_from(&cards("KD,KH,5S"), &state).unwrap();
+        let action = Action::GoOut(Card::Joker, vec![ScoreGroup::Set(set)]);
+        let json = serde_json::to_string(&action).unwrap();
+        assert!(json.contains("{\"Set\":[\"KD\",\"KH\",\"5S\"]}"));
+        let deserialized = serde_json::from_str::<Action>(&json).unwrap();
+        assert_eq!(deserialized, action);
+        // Groups only have to be valid in some round when deserialized; the game checks them
+        // against the actual round
+        let Action::GoOut(_, groups) = deserialized else {
+            unreachable!()
+        };
+        assert_eq!(
+            groups[0].validate(&GameState::new(Rank::Three)),
+            Err(score_group::Error::NotAllSameRank)
+        );
+        assert!(
+            serde_json::from_str::<ScoreGroup>("{\"Run\":[\"3S\",\"3S\",\"7H\",\"7H\"]}").is_err()
+        );
+        assert!(serde_json::from_str::<ScoreGroup>("{\"Set\":[\"3S\",\"4S\",\"5S\"]}").is_err());
+        assert!(serde_json::from_str::<ScoreGroup>("{\"Set\":[]}").is_err());
+
+        let action = EndOfRoundAction {
+            discard: Card::Joker,
+            go_out: Vec::new(),
+            remaining: cards("3S,4S"),
+        };
+        let json = serde_json::to_string(&action).unwrap();
+        assert_eq!(
+            json,
+            "{\"discard\":\"Joker\",\"go_out\":[],\"remaining\":[\"3S\",\"4S\"]}"
+        );
+        assert_eq!(
+            serde_json::from_str::<EndOfRoundAction>(&json).unwrap(),
+            action
+        );
+
+        let hand = Hand::new(cards("3S,Joker"));
+        assert_eq!(serde_json::to_string(&hand).unwrap(), "[\"3S\",\"Joker\"]");
+        let json = serde_json::to_string(&state).unwrap();
+        assert_eq!(serde_json::from_str::<GameState>(&json).unwrap(), state);
+        assert_eq!(serde_json::to_string(&Score(12)).unwrap(), "12");
+        assert_eq!(
+            serde_json::to_string(&DrawLocation::DrawPile).unwrap(),
+            "\"DrawPile\""
+        );
+    }
+}
@@ -0,0 +1,142 @@
+use crate::round_result::RoundResult;
+use crate::rules::TieBreaker;
+use crate::score::Score;
+
+/// The final standings of a finished game.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct GameResult {
+    /// Every player, from first place to last.
+    pub standings: Vec<Standing>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Standing {
+    pub seat: usize,
+    /// 1 for the winner. Players who are still tied after any tie-breaker share a place, and the
+    /// next place is skipped, e.g. 1, 1, 3.
+    pub place: usize,
+    pub score: Score,
+    /// Number of rounds in which this player went out first.
+    pub rounds_won: usize,
+}
+
+impl GameResult {
+    /// Rank the players by lowest score, then by `tie_breaker` if there is one.
+    pub fn new(scores: &[Score], rounds: &[RoundResult], tie_breaker: Option<TieBreaker>) -> Self {
+        let mut standings = scores
+            .iter()
+            .enumerate()
+            .map(|(seat, &score)| Standing {
+                seat,
+                place: 0,
+                score,
+                rounds_won: rounds
+                    .iter()
+                    .filter(|round| round.first_out == Some(seat))
+                    .count(),
+            })
+            .collect::<Vec<_>>();
+        let key = |standing: &Standing| (standing.score, tie_break_key(standing, tie_breaker));
+        standings.sort_by_key(key);
+        for index in 0..standings.len() {
+            standings[index].place =
+                if index > 0 && key(&standings[index - 1]) == key(&standings[index]) {
+                    standings[index - 1].place
+                } else {
+                    index + 1
+                };
+        }
+        GameResult { standings }
+    }
+
+    /// The seats of every player in first place.
+    pub fn winners(&self) -> impl '_ + Iterator<Item = usize> {
+        self.standings
+            .iter()
+            .filter(|standing| standing.place == 1)
+            .map(|standing| standing.seat)
+    }
+
+    /// Whether more than one player finished in first place.
+    pub fn is_tie(&self) -> bool {
+        self.winners().count() > 1
+    }
+}
+
+/// Sort key for breaking ties between players with the same score. Lower is better.
+fn tie_break_key(standing: &Standing, tie_breaker: Option<TieBreaker>) -> isize {
+    match tie_breaker {
+        Some(TieBreaker::MostRoundsWon) => -(standing.rounds_won as isize),
+        None => 0,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rank::Rank;
+
+    fn rounds(first_outs: &[usize]) -> Vec<RoundResult> {
+        first_outs
+            .iter()
+            .map(|&seat| RoundResult {
+                round_rank: Rank::Three,
+                dealer: 0,
+                first_out: Some(seat),
+                players: Vec::new(),
+            })
+            .collect()
+    }
+
+    fn places(result: &GameResult) -> Vec<(usize, usize)> {
+        result
+            .standings
+            .iter()
+            .map(|standing| (standing.seat, standing.place))
+            .collect()
+    }
+
+    #[test]
+    fn test_sorted_by_lowest_score() {
+        let scores = [Score(40), Score(10), Score(25)];
+        let result = GameResult::new(&scores, &rounds(&[1, 1, 0]), None);
+        assert_eq!(places(&result), vec![(1, 1), (2, 2), (0, 3)]);
+        assert_eq!(result.standings[0].score, Score(10));
+        assert_eq!(result.standings[0].rounds_won, 2);
+        assert_eq!(result.winners().collect::<Vec<_>>(), vec![1]);
+        assert!(!result.is_tie());
+    }
+
+    #[test]
+    fn test_tie() {
+        let scores = [Score(30), Score(10), Score(10), Score(5)];
+        let result = GameResult::new(&scores, &rounds(&[1, 2, 2]), None);
+        assert_eq!(places(&result), vec![(3, 1), (1, 2), (2, 2), (0, 4)]);
+
+        let scores = [Score(10), Score(10), Score(30)];
+        let result = GameResult::new(&scores, &rounds(&[1, 2, 2]), None);
+        assert_eq!(places(&result), vec![(0, 1), (1, 1), (2, 3)]);
+        assert_eq!(result.winners().collect::<Vec<_>>(), vec![0, 1]);
+        assert!(result.is_tie());
+    }
+
+    #[test]
+    fn test_tie_breaker() {
+        let scores = [Score(10), Score(10), Score(10), Score(30)];
+        let result = GameResult::new(
+            &scores,
+            &rounds(&[1, 3, 1, 0, 2, 0]),
+            Some(TieBreaker::MostRoundsWon),
+        );
+        assert_eq!(places(&result), vec![(0, 1), (1, 1), (2, 3), (3, 4)]);
+        assert!(result.is_tie());
+
+        let result = GameResult::new(
+            &scores,
+            &rounds(&[1, 3, 1, 0, 2]),
+            Some(TieBreaker::MostRoundsWon),
+        );
+        assert_eq!(places(&result), vec![(1, 1), (0, 2), (2, 2), (3, 4)]);
+        assert!(!result.is_tie());
+    }
+}
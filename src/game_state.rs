@@ -1,14 +1,26 @@
 use crate::card::{Card, NormalCard};
 use crate::rank::Rank;
+use crate::rules::{RuleSet, WildRule};
 
-#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GameState {
-    wild_rank: Rank,
+    round_rank: Rank,
+    rules: RuleSet,
 }
 
 impl GameState {
-    pub fn new(wild_rank: Rank) -> GameState {
-        GameState { wild_rank }
+    /// The state for the round in which `round_rank` cards are dealt, using the official rules.
+    pub fn new(round_rank: Rank) -> GameState {
+        GameState::with_rules(round_rank, RuleSet::official())
+    }
+
+    pub fn with_rules(round_rank: Rank, rules: RuleSet) -> GameState {
+        GameState { round_rank, rules }
+    }
+
+    pub fn rules(&self) -> &RuleSet {
+        &self.rules
     }
 
     pub fn is_card_wild(&self, card: Card) -> bool {
@@ -19,7 +31,7 @@ impl GameState {
     }
 
     pub fn is_rank_wild(&self, rank: Rank) -> bool {
-        self.wild_rank == rank
+        self.wild_rank() == Some(rank)
     }
 
     pub fn non_wild(&self, card: Card) -> Option<NormalCard> {
@@ -28,22 +40,41 @@ impl GameState {
             .cloned()
     }
 
-    pub fn wild_rank(self) -> Rank {
-        self.wild_rank
+    /// The rank matching the number of cards dealt this round.
+    pub fn round_rank(self) -> Rank {
+        self.round_rank
+    }
+
+    /// The rank that is wild this round, besides jokers, if any.
+    pub fn wild_rank(self) -> Option<Rank> {
+        match self.rules.wilds {
+            WildRule::RoundRank => Some(self.round_rank),
+            WildRule::Fixed(rank) => Some(rank),
+            WildRule::JokersOnly => None,
+        }
     }
 
     pub fn round_num(self) -> usize {
-        self.wild_rank.number() - 2
+        self.round_rank.number() - 2
     }
 
     pub fn num_cards(self) -> usize {
-        self.wild_rank.number()
+        self.round_rank.number()
+    }
+
+    /// The state for the following round, or `None` if this is the last (King) round.
+    pub fn next(self) -> Option<GameState> {
+        let rules = self.rules;
+        self.round_rank
+            .next()
+            .map(|rank| GameState::with_rules(rank, rules))
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::suit::Suit;
 
     #[test]
     fn test_round_num() {
@@ -64,4 +95,47 @@ mod test {
         let game_state = GameState::new(Rank::King);
         assert_eq!(game_state.num_cards(), 13);
     }
+
+    #[test]
+    fn test_next() {
+        let game_state = GameState::new(Rank::Three);
+        assert_eq!(
+            game_state.next().map(GameState::round_rank),
+            Some(Rank::Four)
+        );
+        let game_state = GameState::new(Rank::Queen);
+        assert_eq!(
+            game_state.next().map(GameState::round_rank),
+            Some(Rank::King)
+        );
+        let game_state = GameState::new(Rank::King);
+        assert!(game_state.next().is_none());
+    }
+
+    #[test]
+    fn test_wild_rank() {
+        let game_state = GameState::new(Rank::Seven);
+        assert_eq!(game_state.wild_rank(), Some(Rank::Seven));
+        assert!(game_state.is_card_wild(Card::new(Suit::Heart, Rank::Seven)));
+        assert!(game_state.is_card_wild(Card::Joker));
+
+        let rules = RuleSet {
+            wilds: WildRule::Fixed(Rank::Queen),
+            ..RuleSet::official()
+        };
+        let game_state = GameState::with_rules(Rank::Seven, rules);
+        assert_eq!(game_state.wild_rank(), Some(Rank::Queen));
+        assert!(!game_state.is_card_wild(Card::new(Suit::Heart, Rank::Seven)));
+        assert!(game_state.is_card_wild(Card::new(Suit::Heart, Rank::Queen)));
+        assert_eq!(game_state.next().unwrap().wild_rank(), Some(Rank::Queen));
+
+        let rules = RuleSet {
+            wilds: WildRule::JokersOnly,
+            ..RuleSet::official()
+        };
+        let game_state = GameState::with_rules(Rank::Seven, rules);
+        assert_eq!(game_state.wild_rank(), None);
+        assert!(!game_state.is_card_wild(Card::new(Suit::Heart, Rank::Seven)));
+        assert!(game_state.is_card_wild(Card::Joker));
+    }
 }
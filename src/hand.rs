@@ -1,6 +1,10 @@
 use crate::card::Card;
+use crate::parse::{ParseError, ParseErrorKind};
+use std::str::FromStr;
 
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct Hand {
     pub cards: Vec<Card>,
 }
@@ -11,18 +15,64 @@ impl Hand {
     }
 
     pub fn try_from(string: &str) -> Option<Self> {
-        let string = string.trim();
-        // Split will try to parse a single card from the empty string, we don't want
-        // that. Currently, a blank string is a valid input for an empty hand.
-        if string.is_empty() {
-            return Some(Hand::new(Vec::new()));
+        string.parse().ok()
+    }
+
+    /// Parse cards written any of the ways `Card::parse_lenient` accepts, separated by commas,
+    /// whitespace, or nothing at all, e.g. "3s 4\u{2660}, 5S*".
+    pub fn parse_lenient(string: &str) -> Result<Self, ParseError> {
+        let chars = string.chars().collect::<Vec<_>>();
+        let mut index = 0;
+        let mut cards = Vec::new();
+        while index < chars.len() {
+            if chars[index].is_whitespace() || chars[index] == ',' {
+                index += 1;
+                continue;
+            }
+            cards.push(Card::read_lenient(&chars, &mut index)?);
+        }
+        Ok(Hand::new(cards))
+    }
+
+    /// The cards with their suits as glyphs, separated by spaces.
+    pub fn glyph_string(&self) -> String {
+        self.cards
+            .iter()
+            .map(|card| card.glyph_string())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+impl FromStr for Hand {
+    type Err = ParseError;
+
+    /// Parse comma separated cards. Spaces around each card are ignored, and a blank string is an
+    /// empty hand.
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        // Split will try to parse a single card from the empty string, we don't want that
+        if string.trim().is_empty() {
+            return Ok(Hand::new(Vec::new()));
+        }
+        let num_tokens = string.split(',').count();
+        let mut position = 0;
+        let mut cards = Vec::new();
+        for (index, token) in string.split(',').enumerate() {
+            let leading = token.chars().take_while(|c| c.is_whitespace()).count();
+            let card = token.trim();
+            let card_position = position + leading;
+            position += token.chars().count() + 1;
+            if card.is_empty() && index > 0 && index == num_tokens - 1 {
+                // Point at the comma
+                return Err(ParseError::new(",", ParseErrorKind::TrailingComma)
+                    .offset(card_position - leading - 1));
+            }
+            cards.push(
+                card.parse()
+                    .map_err(|e: ParseError| e.offset(card_position))?,
+            );
         }
-        Some(Hand::new(
-            string
-                .split(',')
-                .map(|w| Card::try_from(w.trim()))
-                .collect::<Option<Vec<_>>>()?,
-        ))
+        Ok(Hand::new(cards))
     }
 }
 
@@ -70,4 +120,77 @@ mod test {
         // Invalid suit
         assert_eq!(None, Hand::try_from("7A"));
     }
+
+    #[test]
+    fn test_from_str_errors() {
+        let error = |token: &str, position, kind| ParseError {
+            token: token.to_string(),
+            position,
+            kind,
+        };
+        assert_eq!(
+            "11C,4H".parse::<Hand>(),
+            Err(error("11C", 0, ParseErrorKind::BadRank))
+        );
+        assert_eq!(
+            "4H, 7A".parse::<Hand>(),
+            Err(error("7A", 4, ParseErrorKind::BadSuit))
+        );
+        assert_eq!(
+            "4H,,5H".parse::<Hand>(),
+            Err(error("", 3, ParseErrorKind::Empty))
+        );
+        assert_eq!(
+            "4H,5H,".parse::<Hand>(),
+            Err(error(",", 5, ParseErrorKind::TrailingComma))
+        );
+        assert_eq!(
+            "4H,5H, ".parse::<Hand>(),
+            Err(error(",", 5, ParseErrorKind::TrailingComma))
+        );
+        assert_eq!(
+            "11C,4H".parse::<Hand>().unwrap_err().to_string(),
+            "invalid rank in \"11C\" at position 0"
+        );
+    }
+
+    #[test]
+    fn test_parse_lenient() {
+        let expected = Ok(Hand::try_from("3S,4S,5S,Joker").unwrap());
+        assert_eq!(Hand::parse_lenient("3S,4S,5S,Joker"), expected);
+        assert_eq!(Hand::parse_lenient("3s 4s 5s *"), expected);
+        assert_eq!(Hand::parse_lenient("3S4S5S*"), expected);
+        assert_eq!(
+            Hand::parse_lenient("3\u{2660}, 4\u{2660},5s joker,"),
+            expected
+        );
+        // Glyphs typed on phones often carry a variation selector
+        assert_eq!(
+            Hand::parse_lenient("3\u{2660}\u{FE0F},4\u{2660}\u{FE0F} 5\u{2660}\u{FE0F}*"),
+            expected
+        );
+        assert_eq!(Hand::parse_lenient("  "), Ok(Hand::new(Vec::new())));
+        assert_eq!(
+            Hand::parse_lenient("3S 10H 4X"),
+            Err(ParseError {
+                token: "4X".to_string(),
+                position: 7,
+                kind: ParseErrorKind::BadSuit,
+            })
+        );
+        assert_eq!(
+            Hand::parse_lenient("3S10H1H"),
+            Err(ParseError {
+                token: "1H".to_string(),
+                position: 5,
+                kind: ParseErrorKind::BadRank,
+            })
+        );
+    }
+
+    #[test]
+    fn test_glyph_string() {
+        let hand = Hand::try_from("3S,10H,Joker").unwrap();
+        assert_eq!(hand.glyph_string(), "3\u{2660} 10\u{2665} Joker");
+    }
 }
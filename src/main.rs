@@ -1,23 +1,36 @@
 #![allow(unused_imports, unused_variables, dead_code)]
 
 mod card;
+mod card_multiset;
+mod event;
 mod game;
+mod game_result;
 mod game_state;
 mod hand;
+mod parse;
+mod partition;
 mod player;
+mod player_view;
 mod rank;
+mod record;
+mod round_result;
+mod rules;
+mod runner;
 mod score;
 mod score_group;
+mod snapshot;
 mod suit;
+#[cfg(test)]
+mod test_utils;
 mod utils;
 
 fn main() {
-    for card in card::full_deck() {
-        println!("{}: {}", card, card.score())
+    let game_state = game_state::GameState::new(rank::Rank::Six);
+    for card in card::full_deck(&card::DeckSpec::default()) {
+        println!("{}: {}", card, card.score(&game_state))
     }
 
-    let game_state = game_state::GameState::new(rank::Rank::Six);
-    for hand_str in ["JS,QS,KS", "4H,4D,Joker", "3H,4D,5H"].iter() {
+    for hand_str in ["JS,QS,KS", "4H,4D,Joker", "3H,4D,5H", "11C,4H"].iter() {
         println!("{}:", hand_str);
         for string in hand_info(hand_str, &game_state) {
             println!("\t{}", string);
@@ -25,36 +38,68 @@ fn main() {
     }
 
     println!();
-    let mut game = game::Game::new(2);
+    // Pass a file name to save the game there after each turn, and to resume it on the next run
+    let save_path = std::env::args().nth(1);
+    let mut game = match save_path.as_ref().map(std::fs::read_to_string) {
+        Some(Ok(text)) => {
+            let snapshot = match snapshot::Snapshot::parse(&text) {
+                Ok(snapshot) => snapshot,
+                Err(e) => return println!("Invalid save file: {:?}", e),
+            };
+            match game::Game::from_snapshot(snapshot) {
+                Ok(game) => game,
+                Err(e) => return println!("Can't restore the saved game: {:?}", e),
+            }
+        }
+        // Only start afresh if there's no save yet, so that a save we can't read isn't overwritten
+        Some(Err(e)) if e.kind() != std::io::ErrorKind::NotFound => {
+            return println!("Can't read the save file: {}", e)
+        }
+        _ => game::Game::new(2).unwrap(),
+    };
+    game.add_observer(Box::new(|event: &event::GameEvent| {
+        println!("Event: {:?}", event)
+    }));
     use player::Player;
     let mut player = player::DummyPlayer {
         hand: hand::Hand::new(Vec::new()),
         game_state: game.state(),
     };
-    player.start_game();
+    let seat = game.current_player_index();
+    player.start_game(&game.view(seat).unwrap());
     game.debug_print();
-    player.start_round(
-        player::StartRoundInfo {
-            game_state: game.state(),
-        },
-        hand::Hand::new(game.cur_player().hand.clone()),
-    );
-    let card = game.draw(player.your_draw());
+    player.start_round(&game.view(seat).unwrap());
+    let card = game
+        .draw(player.your_draw(&game.view(seat).unwrap()))
+        .unwrap();
     game.debug_print();
-    game.turn(player.your_turn(card));
+    game.turn(player.your_turn(&game.view(seat).unwrap(), card))
+        .unwrap();
     // game.discard(game.cur_player().hand[0]).unwrap();
     game.debug_print();
+    if let Some(path) = save_path {
+        if let Err(e) = std::fs::write(path, game.snapshot().to_string()) {
+            println!("Can't save the game: {}", e);
+        }
+    }
 }
 
 fn hand_info(hand_str: &str, game_state: &game_state::GameState) -> Vec<String> {
-    let hand = match hand::Hand::try_from(hand_str) {
-        Some(h) => h,
-        None => return vec!["Invalid hand".to_string()],
+    let hand = match hand_str.parse::<hand::Hand>() {
+        Ok(h) => h,
+        Err(e) => return vec![format!("Invalid hand: {}", e)],
     };
-    let set_result = score_group::Set::try_from(&hand.cards, &game_state);
-    let run_result = score_group::Run::try_from(&hand.cards, &game_state);
+    let set_result = score_group::Set::try_from(&hand.cards, game_state);
+    let run_result = score_group::Run::try_from_unordered(&hand.cards, game_state);
+    let (groups, leftover) = partition::best_partition(&hand.cards, game_state);
     vec![
         format!("Set: {:?}", set_result),
         format!("Run: {:?}", run_result),
+        format!("Best partition: {:?}, leftover {:?}", groups, leftover),
+        format!(
+            "Cards needed to go out: {:?}, completed by {} different cards",
+            partition::cards_needed_to_go_out(&hand.cards, game_state),
+            partition::completing_cards(&hand.cards, game_state).len()
+        ),
     ]
 }
@@ -0,0 +1,71 @@
+use std::fmt;
+
+/// Why a card, rank, suit or hand could not be parsed, and where.
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub struct ParseError {
+    /// The offending part of the input, e.g. the whole card `"11C"` in the hand `"4H,11C"`.
+    pub token: String,
+    /// Where `token` starts in the input, counting characters from 0.
+    pub position: usize,
+    pub kind: ParseErrorKind,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub enum ParseErrorKind {
+    /// There was nothing where a card was expected, e.g. `"3S,,4S"`.
+    Empty,
+    /// The rank isn't 3 to 10, J, Q or K.
+    BadRank,
+    /// The suit isn't S, C, H, D or R.
+    BadSuit,
+    /// The hand ends in a comma with no card after it.
+    TrailingComma,
+    /// There was more after a complete card.
+    ExtraCharacters,
+}
+
+impl ParseError {
+    pub(crate) fn new(token: &str, kind: ParseErrorKind) -> Self {
+        ParseError {
+            token: token.to_string(),
+            position: 0,
+            kind,
+        }
+    }
+
+    /// The same error, for a token found `offset` characters further into the input.
+    pub(crate) fn offset(self, offset: usize) -> Self {
+        ParseError {
+            position: self.position + offset,
+            ..self
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            ParseErrorKind::Empty => write!(f, "missing card at position {}", self.position),
+            ParseErrorKind::BadRank => write!(
+                f,
+                "invalid rank in \"{}\" at position {}",
+                self.token, self.position
+            ),
+            ParseErrorKind::BadSuit => write!(
+                f,
+                "invalid suit in \"{}\" at position {}",
+                self.token, self.position
+            ),
+            ParseErrorKind::TrailingComma => {
+                write!(f, "trailing comma at position {}", self.position)
+            }
+            ParseErrorKind::ExtraCharacters => write!(
+                f,
+                "unexpected characters after the card in \"{}\" at position {}",
+                self.token, self.position
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
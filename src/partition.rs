@@ -0,0 +1,582 @@
+use crate::card::{full_deck, Card, NormalCard};
+use crate::card_multiset::CardMultiset;
+use crate::game_state::GameState;
+use crate::rank::{ALL_RANKS, NUM_RANKS};
+use crate::score::Score;
+use crate::score_group::{Run, ScoreGroup, Set};
+use std::collections::HashMap;
+
+/// Split `hand` into runs and sets so that the score of the cards left over is as low as
+/// possible. Returns the groups and the leftover cards, in hand order.
+///
+/// The search is exhaustive, so the result is optimal; it caches the best layout of every
+/// sub-hand it visits, which keeps it fast for hands of up to 14 cards.
+pub fn best_partition(hand: &[Card], game_state: &GameState) -> (Vec<ScoreGroup>, Vec<Card>) {
+    let mut partitioner = Partitioner {
+        game_state,
+        cache: HashMap::new(),
+    };
+    let layout = partitioner.best(&hand.iter().cloned().collect());
+
+    let groups: Vec<ScoreGroup> = layout
+        .melds
+        .iter()
+        .map(|meld| meld.to_score_group(game_state))
+        .collect();
+    let mut grouped: CardMultiset = groups.iter().flat_map(|g| g.cards()).cloned().collect();
+    let leftover = hand
+        .iter()
+        .cloned()
+        .filter(|&card| !grouped.remove(card))
+        .collect();
+    (groups, leftover)
+}
+
+/// The fewest cards in `hand` that would have to be swapped for other cards before the whole
+/// hand could be laid down in runs and sets. Returns `None` if no swaps would do, e.g. for a hand
+/// smaller than the smallest meld.
+pub fn cards_needed_to_go_out(hand: &[Card], game_state: &GameState) -> Option<usize> {
+    if best_partition(hand, game_state).1.is_empty() {
+        return Some(0);
+    }
+    let (naturals, num_wilds) = split_wilds(hand, game_state);
+    let mut distance = Distance::new(game_state);
+    // Trying one replacement, then two, and so on keeps the search small for hands that are
+    // nearly out, which are the ones where the answer matters most
+    (1..=naturals.len())
+        .find(|&budget| distance.can_meld(&naturals, num_wilds as isize, budget, false, false))
+}
+
+/// The distinct cards which, drawn into `hand`, would let the player discard a card and go out
+/// with the rest, in index order. Cards whose every copy is already in `hand` are left out.
+pub fn completing_cards(hand: &[Card], game_state: &GameState) -> Vec<Card> {
+    if cards_needed_to_go_out(hand, game_state).is_none_or(|needed| needed > 1) {
+        return Vec::new();
+    }
+
+    let deck: CardMultiset = full_deck(&game_state.rules().deck).collect();
+    let held: CardMultiset = hand.iter().cloned().collect();
+    let (naturals, num_wilds) = split_wilds(hand, game_state);
+    let mut distance = Distance::new(game_state);
+    let mut cards: Vec<Card> = deck
+        .iter()
+        .filter(|&card| deck.count(card) > held.count(card))
+        .collect();
+    cards.dedup();
+    cards.retain(|&card| {
+        let mut naturals = naturals.clone();
+        let mut num_wilds = num_wilds;
+        if game_state.is_card_wild(card) {
+            num_wilds += 1;
+        } else {
+            naturals.insert(card);
+        }
+        distance.can_meld(&naturals, num_wilds as isize, 0, true, false)
+    });
+    cards
+}
+
+fn split_wilds(hand: &[Card], game_state: &GameState) -> (CardMultiset, usize) {
+    let naturals: CardMultiset = hand
+        .iter()
+        .cloned()
+        .filter(|&card| !game_state.is_card_wild(card))
+        .collect();
+    let num_wilds = hand.len() - naturals.len();
+    (naturals, num_wilds)
+}
+
+#[derive(Debug, Clone)]
+enum Meld {
+    Run(Vec<Card>),
+    Set(Vec<Card>),
+}
+
+impl Meld {
+    fn cards(&self) -> &[Card] {
+        match self {
+            Meld::Run(cards) | Meld::Set(cards) => cards,
+        }
+    }
+
+    fn to_score_group(&self, game_state: &GameState) -> ScoreGroup {
+        match self {
+            Meld::Run(cards) => ScoreGroup::Run(
+                Run::try_from(cards, game_state).expect("Partitioner built an invalid run"),
+            ),
+            Meld::Set(cards) => ScoreGroup::Set(
+                Set::try_from(cards, game_state).expect("Partitioner built an invalid set"),
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Layout {
+    leftover_score: Score,
+    melds: Vec<Meld>,
+}
+
+struct Partitioner<'a> {
+    game_state: &'a GameState,
+    cache: HashMap<CardMultiset, Layout>,
+}
+
+impl Partitioner<'_> {
+    /// Every partition puts the first natural card (or, failing that, the highest scoring wild)
+    /// either in the leftovers or in exactly one meld, so trying each of those choices and
+    /// recursing on what remains covers them all.
+    fn best(&mut self, cards: &CardMultiset) -> Layout {
+        if cards.is_empty() {
+            return Layout {
+                leftover_score: Score::new(),
+                melds: Vec::new(),
+            };
+        }
+        if let Some(layout) = self.cache.get(cards) {
+            return layout.clone();
+        }
+
+        let game_state = self.game_state;
+        let mut wilds: Vec<Card> = cards
+            .iter()
+            .filter(|&card| game_state.is_card_wild(card))
+            .collect();
+        // Melds always take the most expensive wilds first, since any wild can stand in for
+        // any other
+        wilds.sort_by_key(|card| std::cmp::Reverse(card.score(game_state)));
+
+        let melds = match cards.iter().find_map(|card| game_state.non_wild(card)) {
+            Some(card) => self.melds_with_natural(card, cards, &wilds),
+            None => {
+                let card = wilds.remove(0);
+                self.melds_with_wild(card, &wilds)
+            }
+        };
+        let card = melds[0].cards()[0];
+
+        let mut rest = cards.clone();
+        rest.remove(card);
+        let mut best = self.best(&rest);
+        best.leftover_score += card.score(game_state);
+
+        for meld in melds.into_iter().skip(1) {
+            let mut rest = cards.clone();
+            for &card in meld.cards() {
+                rest.remove(card);
+            }
+            let layout = self.best(&rest);
+            if layout.leftover_score < best.leftover_score {
+                best = layout;
+                best.melds.push(meld);
+            }
+        }
+
+        self.cache.insert(cards.clone(), best.clone());
+        best
+    }
+
+    /// The melds `card` could join when only wilds are left. The first entry is `card` alone,
+    /// standing for leaving it over.
+    fn melds_with_wild(&self, card: Card, wilds: &[Card]) -> Vec<Meld> {
+        let rules = self.game_state.rules();
+        let mut melds = vec![Meld::Set(vec![card])];
+        for num_wilds in 1..=wilds.len() {
+            let cards: Vec<Card> = std::iter::once(card)
+                .chain(wilds[..num_wilds].iter().cloned())
+                .collect();
+            if cards.len() >= rules.min_cards_set {
+                melds.push(Meld::Set(cards));
+            } else if cards.len() >= rules.min_cards_run && cards.len() <= NUM_RANKS {
+                melds.push(Meld::Run(cards));
+            }
+        }
+        melds
+    }
+
+    /// The melds `card` could join using the other cards in `cards`. The first entry is `card`
+    /// alone, standing for leaving it over.
+    fn melds_with_natural(
+        &self,
+        card: NormalCard,
+        cards: &CardMultiset,
+        wilds: &[Card],
+    ) -> Vec<Meld> {
+        let rules = self.game_state.rules();
+        let mut melds = vec![Meld::Set(vec![Card::Normal(card)])];
+
+        let mut others = cards.clone();
+        others.remove(Card::Normal(card));
+        let same_rank: Vec<Card> = others
+            .iter()
+            .filter(|&other| other.rank() == Some(card.rank()))
+            .collect();
+        for naturals in sub_multisets(&same_rank) {
+            for num_wilds in 0..=wilds.len() {
+                let set: Vec<Card> = std::iter::once(Card::Normal(card))
+                    .chain(naturals.iter().cloned())
+                    .chain(wilds[..num_wilds].iter().cloned())
+                    .collect();
+                if set.len() >= rules.min_cards_set {
+                    melds.push(Meld::Set(set));
+                }
+            }
+        }
+
+        let position = card.rank().index();
+        for low in 0..=position {
+            for high in position..NUM_RANKS {
+                let len = high - low + 1;
+                if len < rules.min_cards_run {
+                    continue;
+                }
+                let mut run = Vec::with_capacity(len);
+                self.fill_runs(card, &others, wilds, low, high, &mut run, &mut melds);
+            }
+        }
+        melds
+    }
+
+    /// Extend `run` up to rank index `high` in every possible way, filling each position with
+    /// either the matching natural card or the next unused wild.
+    #[allow(clippy::too_many_arguments)]
+    fn fill_runs(
+        &self,
+        card: NormalCard,
+        others: &CardMultiset,
+        wilds: &[Card],
+        low: usize,
+        high: usize,
+        run: &mut Vec<Card>,
+        melds: &mut Vec<Meld>,
+    ) {
+        let position = low + run.len();
+        if position > high {
+            melds.push(Meld::Run(run.clone()));
+            return;
+        }
+        let rank = ALL_RANKS[position];
+        if rank == card.rank() {
+            run.push(Card::Normal(card));
+            self.fill_runs(card, others, wilds, low, high, run, melds);
+            run.pop();
+            return;
+        }
+
+        let natural = Card::Normal(NormalCard::new(card.suit(), rank));
+        if others.contains(natural) && !self.game_state.is_card_wild(natural) {
+            run.push(natural);
+            self.fill_runs(card, others, wilds, low, high, run, melds);
+            run.pop();
+        }
+        let num_wilds = run
+            .iter()
+            .filter(|&&c| self.game_state.is_card_wild(c))
+            .count();
+        if let Some(&wild) = wilds.get(num_wilds) {
+            run.push(wild);
+            self.fill_runs(card, others, wilds, low, high, run, melds);
+            run.pop();
+        }
+    }
+}
+
+/// Searches for ways to meld a whole hand after replacing some of its natural cards. A replaced
+/// card may as well become a wild, so wilds are only counted, and melds are checked by building
+/// them with jokers standing in for the wilds.
+struct Distance<'a> {
+    game_state: &'a GameState,
+    cache: HashMap<(CardMultiset, isize, usize, bool, bool), bool>,
+}
+
+impl<'a> Distance<'a> {
+    fn new(game_state: &'a GameState) -> Self {
+        Distance {
+            game_state,
+            cache: HashMap::new(),
+        }
+    }
+
+    /// Whether `naturals` and `num_wilds` wilds can all be melded after replacing at most
+    /// `budget` of the naturals. Melds may borrow wilds from replacements still to come, so
+    /// `num_wilds` can go negative along the way, but never below what the budget can pay back.
+    /// With `discard`, one card must be thrown away first. `grouped` says whether there is
+    /// already a meld that spare wilds could join.
+    fn can_meld(
+        &mut self,
+        naturals: &CardMultiset,
+        num_wilds: isize,
+        budget: usize,
+        discard: bool,
+        grouped: bool,
+    ) -> bool {
+        if num_wilds + (budget as isize) < 0 {
+            return false;
+        }
+        let key = (naturals.clone(), num_wilds, budget, discard, grouped);
+        if let Some(&result) = self.cache.get(&key) {
+            return result;
+        }
+
+        let game_state = self.game_state;
+        let result = match naturals.iter().next() {
+            None => {
+                let spare = num_wilds - discard as isize;
+                let jokers = vec![Card::Joker; spare.max(0) as usize];
+                spare == 0
+                    || (spare > 0 && grouped)
+                    || Set::try_from(&jokers, game_state).is_ok()
+                    || Run::try_from(&jokers, game_state).is_ok()
+            }
+            Some(card) => {
+                let mut rest = naturals.clone();
+                rest.remove(card);
+                let natural = game_state.non_wild(card).unwrap();
+                let max_wilds = (num_wilds + budget as isize) as usize;
+                (discard && self.can_meld(&rest, num_wilds, budget, false, grouped))
+                    || (budget > 0
+                        && self.can_meld(&rest, num_wilds + 1, budget - 1, discard, grouped))
+                    || melds_to_go_out(natural, &rest, max_wilds, game_state)
+                        .into_iter()
+                        .any(|meld| {
+                            let mut rest = naturals.clone();
+                            let mut num_wilds = num_wilds;
+                            for &card in meld.iter() {
+                                if card.is_joker() {
+                                    num_wilds -= 1;
+                                } else {
+                                    rest.remove(card);
+                                }
+                            }
+                            self.can_meld(&rest, num_wilds, budget, discard, true)
+                        })
+            }
+        };
+
+        self.cache.insert(key, result);
+        result
+    }
+}
+
+/// The melds `card` could join using naturals from `others` and at most `max_wilds` wilds, with
+/// jokers standing in for the wilds. When every card has to be melded, a wild is never better
+/// than a natural that fits the same place, so melds that need wilds take every natural that
+/// fits them.
+fn melds_to_go_out(
+    card: NormalCard,
+    others: &CardMultiset,
+    max_wilds: usize,
+    game_state: &GameState,
+) -> Vec<Vec<Card>> {
+    let mut melds = Vec::new();
+
+    let same_rank: Vec<Card> = others
+        .iter()
+        .filter(|&other| other.rank() == Some(card.rank()))
+        .collect();
+    for naturals in sub_multisets(&same_rank) {
+        let max_wilds = if naturals.len() == same_rank.len() {
+            max_wilds
+        } else {
+            0
+        };
+        let set = (0..=max_wilds)
+            .map(|num_wilds| {
+                std::iter::once(Card::Normal(card))
+                    .chain(naturals.iter().cloned())
+                    .chain(std::iter::repeat_n(Card::Joker, num_wilds))
+                    .collect::<Vec<_>>()
+            })
+            .find(|set| Set::try_from(set, game_state).is_ok());
+        melds.extend(set);
+    }
+
+    let position = card.rank().index();
+    for low in 0..=position {
+        for high in position..NUM_RANKS {
+            let run: Vec<Card> = ALL_RANKS[low..=high]
+                .iter()
+                .map(|&rank| {
+                    let natural = Card::Normal(NormalCard::new(card.suit(), rank));
+                    if rank == card.rank() || others.contains(natural) {
+                        natural
+                    } else {
+                        Card::Joker
+                    }
+                })
+                .collect();
+            let num_wilds = run.iter().filter(|card| card.is_joker()).count();
+            if num_wilds <= max_wilds && Run::try_from(&run, game_state).is_ok() {
+                melds.push(run);
+            }
+        }
+    }
+    melds
+}
+
+/// Every sub-multiset of `cards`, which must be sorted so that equal cards are adjacent.
+fn sub_multisets(cards: &[Card]) -> Vec<Vec<Card>> {
+    let mut subsets = vec![Vec::new()];
+    let mut start = 0;
+    while start < cards.len() {
+        let card = cards[start];
+        let copies = cards[start..].iter().take_while(|&&c| c == card).count();
+        subsets = subsets
+            .into_iter()
+            .flat_map(|subset| {
+                (0..=copies).map(move |n| {
+                    let mut subset = subset.clone();
+                    subset.extend(std::iter::repeat_n(card, n));
+                    subset
+                })
+            })
+            .collect();
+        start += copies;
+    }
+    subsets
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::hand::Hand;
+    use crate::rank::Rank;
+    use crate::rules::RuleSet;
+
+    fn cards_from_str(string: &str) -> Vec<Card> {
+        string.parse::<Hand>().unwrap().cards
+    }
+
+    fn leftover_score(leftover: &[Card], game_state: &GameState) -> Score {
+        leftover.iter().map(|card| card.score(game_state)).sum()
+    }
+
+    fn check(hand: &str, game_state: &GameState) -> (Vec<ScoreGroup>, Vec<Card>) {
+        let hand = cards_from_str(hand);
+        let (groups, leftover) = best_partition(&hand, game_state);
+        let mut cards: CardMultiset = hand.iter().cloned().collect();
+        for card in groups.iter().flat_map(|g| g.cards()).chain(&leftover) {
+            assert!(cards.remove(*card), "{} used twice", card);
+        }
+        assert!(cards.is_empty());
+        for group in &groups {
+            assert_eq!(Ok(()), group.validate(game_state));
+        }
+        (groups, leftover)
+    }
+
+    #[test]
+    fn test_simple() {
+        let game_state = GameState::new(Rank::Six);
+        let (groups, leftover) = check("3S,4S,5S,KD,KH,KC,9H", &game_state);
+        assert_eq!(2, groups.len());
+        assert_eq!(cards_from_str("9H"), leftover);
+
+        let (groups, leftover) = check("", &game_state);
+        assert!(groups.is_empty() && leftover.is_empty());
+
+        let (groups, leftover) = check("3S,10H", &game_state);
+        assert!(groups.is_empty());
+        assert_eq!(cards_from_str("3S,10H"), leftover);
+    }
+
+    #[test]
+    fn test_wilds() {
+        // Sixes are wild; the joker and the wild six fill the gaps in the run
+        let game_state = GameState::new(Rank::Six);
+        let (groups, leftover) = check("7H,Joker,5H,9H,6C,KS", &game_state);
+        assert_eq!(cards_from_str("KS"), leftover);
+        assert_eq!(1, groups.len());
+        assert_eq!(5, groups[0].cards().len());
+
+        // The wild is better spent on the kings than on the threes
+        let (_, leftover) = check("3S,3D,KS,KD,Joker", &game_state);
+        assert_eq!(cards_from_str("3S,3D"), leftover);
+
+        // Wilds on their own still make a meld
+        let (groups, leftover) = check("Joker,6S,6D", &game_state);
+        assert_eq!(1, groups.len());
+        assert!(leftover.is_empty());
+
+        // Spare wilds join a set rather than being left over
+        let (groups, leftover) = check("Joker,6S,JH,JS,3C", &game_state);
+        assert_eq!(1, groups.len());
+        assert_eq!(cards_from_str("3C"), leftover);
+    }
+
+    #[test]
+    fn test_card_in_either_meld() {
+        // 7S can go in the run or the set, but not both
+        let game_state = GameState::new(Rank::Three);
+        let (_, leftover) = check("5S,6S,7S,7H,7D,8S", &game_state);
+        assert_eq!(cards_from_str("7H,7D"), leftover);
+
+        let (groups, leftover) = check("5S,6S,7S,7H,7D,7C", &game_state);
+        assert_eq!(2, groups.len());
+        assert!(leftover.is_empty());
+    }
+
+    #[test]
+    fn test_full_hand() {
+        let game_state = GameState::new(Rank::King);
+        // 9D has to go in the diamond run, leaving the wild king for the other nines
+        let hand = "4S,5S,6S,Joker,8S,9D,9H,9C,QR,QH,QD,KH,JD,10D";
+        let (_, leftover) = check(hand, &game_state);
+        assert!(leftover.is_empty());
+
+        // The two wilds go to the most expensive disjoint pairs: JR,JH and 8D,9D
+        let hand = "3S,5H,7C,9D,JR,QS,4H,6C,8D,10R,KS,KH,3D,JH";
+        let (_, leftover) = check(hand, &game_state);
+        assert_eq!(Score(50), leftover_score(&leftover, &game_state));
+    }
+
+    #[test]
+    fn test_rules() {
+        let rules = RuleSet {
+            min_cards_run: 4,
+            ..RuleSet::default()
+        };
+        let game_state = GameState::with_rules(Rank::Six, rules);
+        let (groups, leftover) = check("3S,4S,5S", &game_state);
+        assert!(groups.is_empty());
+        assert_eq!(3, leftover.len());
+    }
+
+    #[test]
+    fn test_cards_needed_to_go_out() {
+        let game_state = GameState::new(Rank::Three);
+        let needed = |hand| cards_needed_to_go_out(&cards_from_str(hand), &game_state);
+        assert_eq!(Some(0), needed("4S,5S,6S"));
+        assert_eq!(Some(0), needed("4S,3D,6S"));
+        assert_eq!(Some(1), needed("4S,5S,9H"));
+        assert_eq!(Some(2), needed("4S,8H,KD"));
+        assert_eq!(None, needed("4S,8H"));
+
+        // Swapping out KC only frees one wild, but the run and the set need one each
+        assert_eq!(Some(2), needed("4S,5S,7S,9H,9D,KC"));
+        assert_eq!(Some(1), needed("4S,5S,7S,9H,9D,3C"));
+
+        let game_state = GameState::new(Rank::King);
+        let needed = |hand| cards_needed_to_go_out(&cards_from_str(hand), &game_state);
+        assert_eq!(
+            Some(0),
+            needed("3S,4S,5S,6S,7S,8S,9S,10S,JS,QS,3S,4S,5S,6S")
+        );
+        assert_eq!(
+            Some(1),
+            needed("3S,4S,5S,6S,7S,8S,9S,10S,JS,QS,3S,4S,5S,9H")
+        );
+    }
+
+    #[test]
+    fn test_completing_cards() {
+        let game_state = GameState::new(Rank::Three);
+        let mut expected = cards_from_str("6S,3S,3C,3H,3D,3R,Joker");
+        expected.sort_by_key(|card| card.index());
+        let hand = cards_from_str("4S,5S,7S,9H,9D,9C");
+        assert_eq!(expected, completing_cards(&hand, &game_state));
+
+        let hand = cards_from_str("4S,5S,7S,9H,9D,KC");
+        assert!(completing_cards(&hand, &game_state).is_empty());
+    }
+}
@@ -1,30 +1,31 @@
 use crate::game::{Action, EndOfRoundAction};
+use crate::game_result::GameResult;
 use crate::game_state::GameState;
 use crate::hand::Hand;
 use crate::game::DrawLocation;
 use crate::card::Card;
+use crate::player_view::PlayerView;
 use crate::rank::Rank;
 
+/// Every callback gets a `PlayerView` of the game as it is at that moment, from this player's seat.
 pub trait Player {
-    fn start_game(&mut self);
+    fn start_game(&mut self, view: &PlayerView);
 
-    fn start_round(&mut self, start_round_info: StartRoundInfo, hand: Hand);
+    fn start_round(&mut self, view: &PlayerView);
 
-    fn other_player_draw(&mut self, draw_location: DrawLocation);
+    fn other_player_draw(&mut self, view: &PlayerView, draw_location: DrawLocation);
 
-    fn other_player_turn(&mut self, action: Action);
+    fn other_player_turn(&mut self, view: &PlayerView, action: Action);
 
-    fn other_player_last_turn(&mut self, action: EndOfRoundAction);
+    fn other_player_last_turn(&mut self, view: &PlayerView, action: EndOfRoundAction);
 
-    fn your_draw(&mut self) -> DrawLocation;
+    fn your_draw(&mut self, view: &PlayerView) -> DrawLocation;
 
-    fn your_turn(&mut self, card: Card) -> Action;
+    fn your_turn(&mut self, view: &PlayerView, card: Card) -> Action;
 
-    fn your_last_turn(&mut self, card: Card) -> EndOfRoundAction;
-}
+    fn your_last_turn(&mut self, view: &PlayerView, card: Card) -> EndOfRoundAction;
 
-pub struct StartRoundInfo {
-    pub game_state: GameState,
+    fn end_game(&mut self, view: &PlayerView, result: &GameResult);
 }
 
 pub struct DummyPlayer {
@@ -33,29 +34,29 @@ pub struct DummyPlayer {
 }
 
 impl Player for DummyPlayer {
-    fn start_game(&mut self) {}
+    fn start_game(&mut self, view: &PlayerView) {}
 
-    fn start_round(&mut self, start_round_info: StartRoundInfo, hand: Hand) {
-        self.hand = hand;
-        self.game_state = start_round_info.game_state;
+    fn start_round(&mut self, view: &PlayerView) {
+        self.hand = view.hand.clone();
+        self.game_state = view.game_state;
     }
 
-    fn other_player_draw(&mut self, draw_location: DrawLocation) {}
+    fn other_player_draw(&mut self, view: &PlayerView, draw_location: DrawLocation) {}
 
-    fn other_player_turn(&mut self, action: Action) {}
+    fn other_player_turn(&mut self, view: &PlayerView, action: Action) {}
 
-    fn other_player_last_turn(&mut self, action: EndOfRoundAction) {}
+    fn other_player_last_turn(&mut self, view: &PlayerView, action: EndOfRoundAction) {}
 
-    fn your_draw(&mut self) -> DrawLocation {
+    fn your_draw(&mut self, view: &PlayerView) -> DrawLocation {
         DrawLocation::DrawPile
     }
 
-    fn your_turn(&mut self, card: Card) -> Action {
+    fn your_turn(&mut self, view: &PlayerView, card: Card) -> Action {
         self.hand.cards.push(card);
         Action::Discard(self.hand.cards.remove(0))
     }
 
-    fn your_last_turn(&mut self, card: Card) -> EndOfRoundAction {
+    fn your_last_turn(&mut self, view: &PlayerView, card: Card) -> EndOfRoundAction {
         self.hand.cards.push(card);
         let discard = self.hand.cards.remove(0);
         let remaining = self.hand.cards.clone();
@@ -65,4 +66,6 @@ impl Player for DummyPlayer {
             remaining,
         }
     }
+
+    fn end_game(&mut self, view: &PlayerView, result: &GameResult) {}
 }
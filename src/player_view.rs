@@ -0,0 +1,39 @@
+use crate::card::Card;
+use crate::game::Phase;
+use crate::game_state::GameState;
+use crate::hand::Hand;
+use crate::score::Score;
+
+/// What the player in `seat` is allowed to see: everything that is public, plus their own hand.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PlayerView {
+    pub seat: usize,
+    pub hand: Hand,
+    pub game_state: GameState,
+    pub phase: Phase,
+    pub dealer: usize,
+    pub current_player: usize,
+    /// The seat of the player who went out this round, if anyone has.
+    pub first_out: Option<usize>,
+    /// The card on top of the discard pile, which can be drawn.
+    pub discard_top: Option<Card>,
+    /// Number of cards left in the draw pile.
+    pub draw_pile_size: usize,
+    /// Whether drawing from the draw pile is off the table: it's empty, there's nothing to
+    /// reshuffle, and `ExhaustionPolicy::ForceDiscardDraw` says to take the top discard instead.
+    pub draw_pile_forbidden: bool,
+    /// What everybody can see about each player, indexed by seat.
+    pub players: Vec<PublicPlayerInfo>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PublicPlayerInfo {
+    pub score: Score,
+    pub hand_size: usize,
+}
+
+impl PlayerView {
+    pub fn is_my_turn(&self) -> bool {
+        self.current_player == self.seat
+    }
+}
@@ -1,5 +1,7 @@
 use std::fmt;
+use std::str::FromStr;
 
+use crate::parse::{ParseError, ParseErrorKind};
 use crate::score::Score;
 
 #[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Copy, Clone)]
@@ -68,7 +70,7 @@ impl Rank {
         ALL_RANKS.get(index).cloned()
     }
 
-    fn index(&self) -> usize {
+    pub(crate) fn index(&self) -> usize {
         match self {
             Rank::Three => 0,
             Rank::Four => 1,
@@ -85,20 +87,24 @@ impl Rank {
     }
 
     pub fn try_from(string: &str) -> Option<Self> {
-        Some(match string {
-            "3" => Rank::Three,
-            "4" => Rank::Four,
-            "5" => Rank::Five,
-            "6" => Rank::Six,
-            "7" => Rank::Seven,
-            "8" => Rank::Eight,
-            "9" => Rank::Nine,
-            "10" => Rank::Ten,
-            "J" => Rank::Jack,
-            "Q" => Rank::Queen,
-            "K" => Rank::King,
-            _ => return None,
-        })
+        string.parse().ok()
+    }
+
+    /// The rank's name, e.g. "Ten".
+    pub fn name(self) -> &'static str {
+        match self {
+            Rank::Three => "Three",
+            Rank::Four => "Four",
+            Rank::Five => "Five",
+            Rank::Six => "Six",
+            Rank::Seven => "Seven",
+            Rank::Eight => "Eight",
+            Rank::Nine => "Nine",
+            Rank::Ten => "Ten",
+            Rank::Jack => "Jack",
+            Rank::Queen => "Queen",
+            Rank::King => "King",
+        }
     }
 
     pub fn steps_to(&self, dest: Rank) -> isize {
@@ -132,6 +138,41 @@ impl Iterator for RankRange {
     }
 }
 
+impl FromStr for Rank {
+    type Err = ParseError;
+
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        Ok(match string {
+            "3" => Rank::Three,
+            "4" => Rank::Four,
+            "5" => Rank::Five,
+            "6" => Rank::Six,
+            "7" => Rank::Seven,
+            "8" => Rank::Eight,
+            "9" => Rank::Nine,
+            "10" => Rank::Ten,
+            "J" => Rank::Jack,
+            "Q" => Rank::Queen,
+            "K" => Rank::King,
+            _ => return Err(ParseError::new(string, ParseErrorKind::BadRank)),
+        })
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Rank {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        crate::utils::serialize_display(self, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Rank {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        crate::utils::deserialize_from_str(deserializer)
+    }
+}
+
 impl fmt::Display for Rank {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let symbol = match self {
@@ -234,4 +275,13 @@ mod test {
         assert_eq!(None, Rank::try_from("R"));
         assert_eq!(None, Rank::try_from(""));
     }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!(Ok(Rank::Ten), "10".parse());
+        assert_eq!(
+            Err(ParseError::new("11", ParseErrorKind::BadRank)),
+            "11".parse::<Rank>()
+        );
+    }
 }
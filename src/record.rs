@@ -0,0 +1,562 @@
+use crate::card::{Card, DeckSpec};
+use crate::game::{self, Action, DrawLocation, EndOfRoundAction, Game};
+use crate::game_state::GameState;
+use crate::rank::Rank;
+use crate::rules::{ExhaustionPolicy, RuleSet, TieBreaker, WildRule};
+use crate::score::Score;
+use crate::score_group::{Run, ScoreGroup, Set};
+use std::fmt;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub enum Error {
+    /// The line (counting from 1) could not be parsed.
+    InvalidLine(usize),
+    /// The record doesn't say how many players there are.
+    MissingPlayers,
+    /// The record doesn't say what the seed is.
+    MissingSeed,
+    /// The game could not be created from the record's players and rules.
+    InvalidGame(game::Error),
+    /// The move at this index (counting from 0) was rejected by the game.
+    InvalidMove { index: usize, error: game::Error },
+}
+
+/// Everything needed to reproduce a game exactly: the players, rules and seed it was created with,
+/// and every move made since.
+///
+/// The text form has one item per line. A header gives the number of players, the seed, and any
+/// rules, followed by one line per move:
+///
+/// ```text
+/// players 2
+/// seed 1234
+/// rule exhaustion end-round
+/// draw pile
+/// discard 5H
+/// draw discard
+/// go-out 7S run:3S,4S,5S set:KD,KH,Joker
+/// draw pile
+/// last-turn 4S set:QC,QH,QS left:KS,7H
+/// next-round
+/// ```
+///
+/// Rules that are left out are official. Blank lines and lines starting with `#` are ignored.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct GameRecord {
+    pub num_players: usize,
+    pub seed: u64,
+    pub rules: RuleSet,
+    pub moves: Vec<Move>,
+}
+
+/// One call to `Game` that changed the game: a successful one, or a draw that ran out of cards
+/// and ended the round.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Move {
+    Draw(DrawLocation),
+    Turn(Action),
+    LastTurn(EndOfRoundAction),
+    NextRound,
+}
+
+/// Steps through a `GameRecord`, one move at a time.
+pub struct Replay {
+    record: GameRecord,
+    game: Game,
+    position: usize,
+}
+
+impl GameRecord {
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut num_players = None;
+        let mut seed = None;
+        let mut rules = RuleSet::official();
+        let mut moves = Vec::new();
+        // Groups are only valid for the round they were made in, so keep track of it
+        let mut state = GameState::with_rules(Rank::Three, rules);
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = Error::InvalidLine(index + 1);
+            let mut words = line.split_whitespace();
+            let keyword = words.next().ok_or_else(|| invalid.clone())?;
+            let args = words.collect::<Vec<_>>();
+            let header = moves.is_empty();
+            match (keyword, args.as_slice()) {
+                ("players", [n]) if header => {
+                    num_players = Some(n.parse().map_err(|_| invalid)?);
+                }
+                ("seed", [n]) if header => {
+                    seed = Some(n.parse().map_err(|_| invalid)?);
+                }
+                ("rule", [name, value]) if header => {
+                    parse_rule(&mut rules, name, value).ok_or(invalid)?;
+                    state = GameState::with_rules(Rank::Three, rules);
+                }
+                _ => {
+                    let mv = parse_move(keyword, &args, &state).ok_or(invalid)?;
+                    if mv == Move::NextRound {
+                        state = state.next().unwrap_or(state);
+                    }
+                    moves.push(mv);
+                }
+            }
+        }
+        Ok(GameRecord {
+            num_players: num_players.ok_or(Error::MissingPlayers)?,
+            seed: seed.ok_or(Error::MissingSeed)?,
+            rules,
+            moves,
+        })
+    }
+
+    /// Create the game and play every move.
+    pub fn replay(&self) -> Result<Game> {
+        let mut replay = Replay::new(self.clone())?;
+        replay.seek(self.moves.len())?;
+        Ok(replay.into_game())
+    }
+}
+
+impl fmt::Display for GameRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "players {}", self.num_players)?;
+        writeln!(f, "seed {}", self.seed)?;
+        write_rules(f, &self.rules)?;
+        for mv in self.moves.iter() {
+            writeln!(f, "{}", mv)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Move::Draw(DrawLocation::DrawPile) => write!(f, "draw pile"),
+            Move::Draw(DrawLocation::DiscardPile) => write!(f, "draw discard"),
+            Move::Turn(Action::Discard(card)) => write!(f, "discard {}", card),
+            Move::Turn(Action::GoOut(card, groups)) => {
+                write!(f, "go-out {}", card)?;
+                write_melds(f, groups, &[])
+            }
+            Move::LastTurn(action) => {
+                write!(f, "last-turn {}", action.discard)?;
+                write_melds(f, &action.go_out, &action.remaining)
+            }
+            Move::NextRound => write!(f, "next-round"),
+        }
+    }
+}
+
+impl Replay {
+    /// Start replaying `record` from before its first move.
+    pub fn new(record: GameRecord) -> Result<Self> {
+        let game = new_game(&record)?;
+        Ok(Replay {
+            record,
+            game,
+            position: 0,
+        })
+    }
+
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    pub fn into_game(self) -> Game {
+        self.game
+    }
+
+    pub fn record(&self) -> &GameRecord {
+        &self.record
+    }
+
+    /// The number of moves played so far.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn len(&self) -> usize {
+        self.record.moves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.record.moves.is_empty()
+    }
+
+    /// Play the next move. Returns `false` if there are no moves left.
+    pub fn step(&mut self) -> Result<bool> {
+        let mv = match self.record.moves.get(self.position) {
+            Some(mv) => mv,
+            None => return Ok(false),
+        };
+        self.game.apply(mv).map_err(|error| Error::InvalidMove {
+            index: self.position,
+            error,
+        })?;
+        self.position += 1;
+        Ok(true)
+    }
+
+    /// Go to the position after `position` moves have been played. Going backwards replays the
+    /// game from the start. Positions past the end stop at the end.
+    pub fn seek(&mut self, position: usize) -> Result<()> {
+        if position < self.position {
+            self.game = new_game(&self.record)?;
+            self.position = 0;
+        }
+        while self.position < position && self.step()? {}
+        Ok(())
+    }
+}
+
+fn new_game(record: &GameRecord) -> Result<Game> {
+    Game::with_rules_and_seed(record.num_players, record.rules, record.seed)
+        .map_err(Error::InvalidGame)
+}
+
+pub(crate) fn join_cards(cards: &[Card]) -> String {
+    cards
+        .iter()
+        .map(|card| card.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Write each group, then any leftover cards, each preceded by a space.
+pub(crate) fn write_melds(
+    f: &mut fmt::Formatter<'_>,
+    groups: &[ScoreGroup],
+    leftover: &[Card],
+) -> fmt::Result {
+    for group in groups {
+        let kind = match group {
+            ScoreGroup::Run(_) => "run",
+            ScoreGroup::Set(_) => "set",
+        };
+        write!(f, " {}:{}", kind, join_cards(group.cards()))?;
+    }
+    if !leftover.is_empty() {
+        write!(f, " left:{}", join_cards(leftover))?;
+    }
+    Ok(())
+}
+
+/// Parse what `write_melds` wrote, checking that the groups are valid for `game_state`.
+pub(crate) fn parse_melds(
+    args: &[&str],
+    game_state: &GameState,
+) -> Option<(Vec<ScoreGroup>, Vec<Card>)> {
+    let (leftover, groups) = match args.split_last() {
+        Some((last, groups)) if last.starts_with("left:") => {
+            (parse_cards(&last["left:".len()..])?, groups)
+        }
+        _ => (Vec::new(), args),
+    };
+    let groups = groups
+        .iter()
+        .map(|group| parse_group(group, game_state))
+        .collect::<Option<_>>()?;
+    Some((groups, leftover))
+}
+
+pub(crate) fn parse_cards(string: &str) -> Option<Vec<Card>> {
+    string.split(',').map(Card::try_from).collect()
+}
+
+pub(crate) fn parse_move(keyword: &str, args: &[&str], game_state: &GameState) -> Option<Move> {
+    Some(match (keyword, args) {
+        ("draw", ["pile"]) => Move::Draw(DrawLocation::DrawPile),
+        ("draw", ["discard"]) => Move::Draw(DrawLocation::DiscardPile),
+        ("discard", [card]) => Move::Turn(Action::Discard(Card::try_from(card)?)),
+        ("go-out", [card, rest @ ..]) => match parse_melds(rest, game_state)? {
+            (groups, leftover) if leftover.is_empty() => {
+                Move::Turn(Action::GoOut(Card::try_from(card)?, groups))
+            }
+            _ => return None,
+        },
+        ("last-turn", [card, rest @ ..]) => {
+            let (go_out, remaining) = parse_melds(rest, game_state)?;
+            Move::LastTurn(EndOfRoundAction {
+                discard: Card::try_from(card)?,
+                go_out,
+                remaining,
+            })
+        }
+        ("next-round", []) => Move::NextRound,
+        _ => return None,
+    })
+}
+
+fn parse_group(string: &str, game_state: &GameState) -> Option<ScoreGroup> {
+    let mut parts = string.splitn(2, ':');
+    let kind = parts.next()?;
+    let cards = parse_cards(parts.next()?)?;
+    match kind {
+        "run" => Run::try_from(&cards, game_state).ok().map(ScoreGroup::Run),
+        "set" => Set::try_from(&cards, game_state).ok().map(ScoreGroup::Set),
+        _ => None,
+    }
+}
+
+pub(crate) fn write_rules(f: &mut fmt::Formatter<'_>, rules: &RuleSet) -> fmt::Result {
+    let wilds = match rules.wilds {
+        WildRule::RoundRank => "round-rank".to_string(),
+        WildRule::Fixed(rank) => format!("fixed:{}", rank),
+        WildRule::JokersOnly => "jokers-only".to_string(),
+    };
+    let exhaustion = match rules.exhaustion {
+        ExhaustionPolicy::EndRound => "end-round",
+        ExhaustionPolicy::FreshDeck => "fresh-deck",
+        ExhaustionPolicy::ForceDiscardDraw => "force-discard-draw",
+    };
+    let tie_breaker = match rules.tie_breaker {
+        None => "none",
+        Some(TieBreaker::MostRoundsWon) => "most-rounds-won",
+    };
+    writeln!(f, "rule copies_per_card {}", rules.deck.copies_per_card)?;
+    writeln!(f, "rule num_jokers {}", rules.deck.num_jokers)?;
+    writeln!(f, "rule min_players {}", rules.min_players)?;
+    writeln!(f, "rule max_players {}", rules.max_players)?;
+    writeln!(f, "rule joker_score {}", rules.joker_score)?;
+    writeln!(f, "rule wild_score {}", rules.wild_score)?;
+    writeln!(f, "rule min_cards_run {}", rules.min_cards_run)?;
+    writeln!(f, "rule min_cards_set {}", rules.min_cards_set)?;
+    writeln!(f, "rule wilds {}", wilds)?;
+    writeln!(f, "rule exhaustion {}", exhaustion)?;
+    writeln!(f, "rule tie_breaker {}", tie_breaker)
+}
+
+/// Set the rule called `name`, as written by `write_rules`.
+pub(crate) fn parse_rule(rules: &mut RuleSet, name: &str, value: &str) -> Option<()> {
+    match name {
+        "copies_per_card" => rules.deck = DeckSpec::new(value.parse().ok()?, rules.deck.num_jokers),
+        "num_jokers" => rules.deck = DeckSpec::new(rules.deck.copies_per_card, value.parse().ok()?),
+        "min_players" => rules.min_players = value.parse().ok()?,
+        "max_players" => rules.max_players = value.parse().ok()?,
+        "joker_score" => rules.joker_score = Score(value.parse().ok()?),
+        "wild_score" => rules.wild_score = Score(value.parse().ok()?),
+        "min_cards_run" => rules.min_cards_run = value.parse().ok()?,
+        "min_cards_set" => rules.min_cards_set = value.parse().ok()?,
+        "wilds" => {
+            rules.wilds = match value {
+                "round-rank" => WildRule::RoundRank,
+                "jokers-only" => WildRule::JokersOnly,
+                _ if value.starts_with("fixed:") => {
+                    WildRule::Fixed(Rank::try_from(&value["fixed:".len()..])?)
+                }
+                _ => return None,
+            }
+        }
+        "exhaustion" => {
+            rules.exhaustion = match value {
+                "end-round" => ExhaustionPolicy::EndRound,
+                "fresh-deck" => ExhaustionPolicy::FreshDeck,
+                "force-discard-draw" => ExhaustionPolicy::ForceDiscardDraw,
+                _ => return None,
+            }
+        }
+        "tie_breaker" => {
+            rules.tie_breaker = match value {
+                "none" => None,
+                "most-rounds-won" => Some(TieBreaker::MostRoundsWon),
+                _ => return None,
+            }
+        }
+        _ => return None,
+    }
+    Some(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::play_round_going_out;
+
+    /// Play `turns` turns where every player draws and discards their first card.
+    fn discard_game(rules: RuleSet, turns: usize) -> Game {
+        let mut game = Game::with_rules_and_seed(3, rules, 99).unwrap();
+        for turn in 0..turns {
+            let location = match turn % 3 {
+                0 => DrawLocation::DiscardPile,
+                _ => DrawLocation::DrawPile,
+            };
+            game.draw(location).unwrap();
+            game.discard(game.cur_player().hand[0]).unwrap();
+        }
+        game
+    }
+
+    fn assert_same_position(a: &Game, b: &Game) {
+        assert_eq!(a.events(), b.events());
+        for seat in 0..a.players().len() {
+            assert_eq!(a.view(seat), b.view(seat));
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let rules = RuleSet {
+            deck: DeckSpec::new(0, 100),
+            wilds: WildRule::Fixed(Rank::Jack),
+            exhaustion: ExhaustionPolicy::EndRound,
+            tie_breaker: Some(TieBreaker::MostRoundsWon),
+            joker_score: Score(25),
+            min_cards_run: 4,
+            ..RuleSet::official()
+        };
+        let mut game = Game::with_rules_and_seed(2, rules, 7).unwrap();
+        play_round_going_out(&mut game);
+        game.next_round().unwrap();
+        play_round_going_out(&mut game);
+        game.next_round().unwrap();
+        game.draw(DrawLocation::DiscardPile).unwrap();
+
+        let record = game.record();
+        assert_eq!(record.moves.len(), 11);
+        let text = record.to_string();
+        assert!(text.contains("rule wilds fixed:J\n"));
+        assert!(text.contains("go-out Joker set:Joker,Joker,Joker\n"));
+        assert!(text.contains("last-turn Joker set:Joker,Joker,Joker,Joker\n"));
+        assert_eq!(GameRecord::parse(&text), Ok(record.clone()));
+        assert_same_position(&record.replay().unwrap(), &game);
+    }
+
+    #[test]
+    fn test_replay_reshuffles() {
+        let game = discard_game(RuleSet::official(), 200);
+        let record = GameRecord::parse(&game.record().to_string()).unwrap();
+        assert_same_position(&record.replay().unwrap(), &game);
+        assert!(game
+            .events()
+            .iter()
+            .any(|event| matches!(event, crate::event::GameEvent::Reshuffled { .. })));
+    }
+
+    #[test]
+    fn test_replay_seek() {
+        let record = discard_game(RuleSet::official(), 20).record();
+        let mut replay = Replay::new(record).unwrap();
+        assert_eq!(replay.len(), 40);
+        assert_eq!(replay.position(), 0);
+        replay.seek(24).unwrap();
+        assert_eq!(replay.position(), 24);
+        assert_same_position(replay.game(), &discard_game(RuleSet::official(), 12));
+        assert_eq!(replay.step(), Ok(true));
+        assert_eq!(replay.step(), Ok(true));
+        assert_same_position(replay.game(), &discard_game(RuleSet::official(), 13));
+        replay.seek(10).unwrap();
+        assert_eq!(replay.position(), 10);
+        assert_same_position(replay.game(), &discard_game(RuleSet::official(), 5));
+        replay.seek(100).unwrap();
+        assert_eq!(replay.position(), 40);
+        assert_eq!(replay.step(), Ok(false));
+    }
+
+    #[test]
+    fn test_replay_deck_exhausted() {
+        let rules = RuleSet {
+            deck: DeckSpec::new(0, 27),
+            exhaustion: ExhaustionPolicy::EndRound,
+            ..RuleSet::official()
+        };
+        let mut game = Game::with_rules_and_seed(2, rules, 3).unwrap();
+        while game.state().round_rank() != Rank::King {
+            play_round_going_out(&mut game);
+            game.next_round().unwrap();
+        }
+        // Every card is dealt in the King round, leaving only the top discard
+        assert_eq!(
+            game.draw(DrawLocation::DrawPile),
+            Err(game::Error::DeckExhausted)
+        );
+        assert!(game.is_round_over());
+        let replayed = game.record().replay().unwrap();
+        assert!(replayed.is_round_over());
+        assert_same_position(&replayed, &game);
+    }
+
+    #[test]
+    fn test_parse() {
+        let text = "
+            # A short game
+            players 3
+            seed 12
+            rule exhaustion force-discard-draw
+
+            draw discard
+            discard 10H
+        ";
+        let record = GameRecord::parse(text).unwrap();
+        assert_eq!(record.num_players, 3);
+        assert_eq!(record.seed, 12);
+        assert_eq!(
+            record.rules,
+            RuleSet {
+                exhaustion: ExhaustionPolicy::ForceDiscardDraw,
+                ..RuleSet::official()
+            }
+        );
+        assert_eq!(
+            record.moves,
+            vec![
+                Move::Draw(DrawLocation::DiscardPile),
+                Move::Turn(Action::Discard(Card::try_from("10H").unwrap())),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(GameRecord::parse("seed 1"), Err(Error::MissingPlayers));
+        assert_eq!(GameRecord::parse("players 2"), Err(Error::MissingSeed));
+        assert_eq!(
+            GameRecord::parse("players 2\nseed x"),
+            Err(Error::InvalidLine(2))
+        );
+        assert_eq!(
+            GameRecord::parse("players 2\nseed 1\nrule wilds sevens"),
+            Err(Error::InvalidLine(3))
+        );
+        // Header lines have to come before the moves
+        assert_eq!(
+            GameRecord::parse("players 2\ndraw pile\nseed 1"),
+            Err(Error::InvalidLine(3))
+        );
+        // Groups have to be valid for the round
+        assert_eq!(
+            GameRecord::parse("players 2\nseed 1\ngo-out 3S run:4S,6S,7S"),
+            Err(Error::InvalidLine(3))
+        );
+    }
+
+    #[test]
+    fn test_replay_invalid_move() {
+        let record = GameRecord {
+            num_players: 2,
+            seed: 1,
+            rules: RuleSet::official(),
+            moves: vec![Move::Draw(DrawLocation::DrawPile), Move::NextRound],
+        };
+        assert_eq!(
+            record.replay().err(),
+            Some(Error::InvalidMove {
+                index: 1,
+                error: game::Error::WrongPhase,
+            })
+        );
+        let record = GameRecord {
+            num_players: 1,
+            ..record
+        };
+        assert_eq!(
+            record.replay().err(),
+            Some(Error::InvalidGame(game::Error::InvalidPlayerCount(1)))
+        );
+    }
+}
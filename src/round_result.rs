@@ -0,0 +1,32 @@
+use crate::card::Card;
+use crate::rank::Rank;
+use crate::score::Score;
+use crate::score_group::ScoreGroup;
+
+/// The outcome of a single round, for a round-by-round score sheet.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct RoundResult {
+    /// The rank matching the number of cards dealt in the round.
+    pub round_rank: Rank,
+    /// The seat of the player who dealt the round.
+    pub dealer: usize,
+    /// The seat of the player who went out first, or `None` if the round ended without anybody
+    /// going out.
+    pub first_out: Option<usize>,
+    /// Each player's result, indexed by seat.
+    pub players: Vec<PlayerRoundResult>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PlayerRoundResult {
+    pub melds: Vec<ScoreGroup>,
+    /// Cards that weren't part of any meld.
+    pub leftover: Vec<Card>,
+    pub points: Score,
+}
+
+impl RoundResult {
+    pub fn points(&self) -> impl '_ + Iterator<Item = Score> {
+        self.players.iter().map(|player| player.points)
+    }
+}
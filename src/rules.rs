@@ -0,0 +1,105 @@
+use crate::card::DeckSpec;
+use crate::rank::Rank;
+use crate::score::Score;
+
+const OFFICIAL_JOKER_SCORE: Score = Score(50);
+const OFFICIAL_WILD_SCORE: Score = Score(20);
+const OFFICIAL_MIN_CARDS: usize = 3;
+const OFFICIAL_MIN_PLAYERS: usize = 2;
+const OFFICIAL_MAX_PLAYERS: usize = 7;
+
+/// Every rule that can be changed between games. Start from `RuleSet::official()` and use struct
+/// update syntax for house rules.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RuleSet {
+    pub deck: DeckSpec,
+    /// Games always need at least two players, even if this is lower.
+    pub min_players: usize,
+    pub max_players: usize,
+    /// Points for a joker left in hand at the end of a round.
+    pub joker_score: Score,
+    /// Points for a wild card (other than a joker) left in hand at the end of a round.
+    pub wild_score: Score,
+    pub min_cards_run: usize,
+    pub min_cards_set: usize,
+    pub wilds: WildRule,
+    pub exhaustion: ExhaustionPolicy,
+    /// How to separate players with the same final score. With `None` they share the place.
+    pub tie_breaker: Option<TieBreaker>,
+}
+
+/// Which rank, if any, is wild in a given round. Jokers are always wild.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum WildRule {
+    /// The rank matching the number of cards dealt is wild, e.g. threes in the first round.
+    RoundRank,
+    /// The same rank is wild in every round.
+    Fixed(Rank),
+    /// Only jokers are wild.
+    JokersOnly,
+}
+
+/// What happens when a player draws from an empty draw pile and there are no discarded cards
+/// (other than the top one) to reshuffle into a new one.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ExhaustionPolicy {
+    /// The round ends immediately and nobody scores, not even players who already had their last
+    /// turn.
+    EndRound,
+    /// A fresh deck is shuffled to become the draw pile.
+    FreshDeck,
+    /// The draw pile can't be drawn from, so players have to take the top discard instead.
+    ForceDiscardDraw,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TieBreaker {
+    /// The player who went out first in the most rounds wins.
+    MostRoundsWon,
+}
+
+impl RuleSet {
+    pub fn official() -> Self {
+        RuleSet {
+            deck: DeckSpec::official(),
+            min_players: OFFICIAL_MIN_PLAYERS,
+            max_players: OFFICIAL_MAX_PLAYERS,
+            joker_score: OFFICIAL_JOKER_SCORE,
+            wild_score: OFFICIAL_WILD_SCORE,
+            min_cards_run: OFFICIAL_MIN_CARDS,
+            min_cards_set: OFFICIAL_MIN_CARDS,
+            wilds: WildRule::RoundRank,
+            exhaustion: ExhaustionPolicy::FreshDeck,
+            tie_breaker: None,
+        }
+    }
+
+    /// Number of cards needed to deal the biggest (King) round to `num_players` and still start a
+    /// discard pile.
+    pub fn cards_needed(&self, num_players: usize) -> usize {
+        num_players * Rank::King.number() + 1
+    }
+}
+
+impl Default for RuleSet {
+    fn default() -> Self {
+        RuleSet::official()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_cards_needed() {
+        let rules = RuleSet::official();
+        assert_eq!(rules.cards_needed(2), 27);
+        assert_eq!(rules.cards_needed(7), 92);
+        assert!(rules.cards_needed(rules.max_players) <= rules.deck.num_cards());
+    }
+}
@@ -0,0 +1,353 @@
+use crate::card::Card;
+use crate::game::{Action, DrawLocation, Error, Game, Result};
+use crate::game_result::GameResult;
+use crate::player::Player;
+use crate::player_view::PlayerView;
+use crate::round_result::RoundResult;
+use crate::rules::ExhaustionPolicy;
+
+/// Drives a set of `Player` implementations through a full match, calling each of their callbacks
+/// in turn.
+pub struct GameRunner {
+    players: Vec<Box<dyn Player>>,
+    game: Game,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct MatchResult {
+    pub result: GameResult,
+    pub rounds: Vec<RoundResult>,
+}
+
+impl GameRunner {
+    /// Returns `PlayerSeatMismatch` if there isn't exactly one player per seat in `game`.
+    pub fn new(players: Vec<Box<dyn Player>>, game: Game) -> Result<Self> {
+        let seats = game.players().len();
+        if players.len() != seats {
+            return Err(Error::PlayerSeatMismatch {
+                players: players.len(),
+                seats,
+            });
+        }
+        Ok(GameRunner { players, game })
+    }
+
+    /// Play the match to completion. Returns an error if any player makes an invalid move.
+    pub fn run(mut self) -> Result<MatchResult> {
+        for (seat, player) in self.players.iter_mut().enumerate() {
+            player.start_game(&view(&self.game, seat));
+        }
+        loop {
+            self.play_round()?;
+            if !self.game.next_round()? {
+                break;
+            }
+        }
+        let result = self.game.result().ok_or(Error::WrongPhase)?;
+        for (seat, player) in self.players.iter_mut().enumerate() {
+            player.end_game(&view(&self.game, seat), &result);
+        }
+        Ok(MatchResult {
+            result,
+            rounds: self.game.round_results().to_vec(),
+        })
+    }
+
+    fn play_round(&mut self) -> Result<()> {
+        for (seat, player) in self.players.iter_mut().enumerate() {
+            player.start_round(&view(&self.game, seat));
+        }
+
+        loop {
+            match self.play_turn() {
+                Ok(true) => break,
+                Ok(false) => {}
+                Err(err) => return self.round_ended_early(err),
+            }
+        }
+
+        while !self.game.is_round_over() {
+            if let Err(err) = self.play_last_turn() {
+                return self.round_ended_early(err);
+            }
+        }
+        Ok(())
+    }
+
+    /// Errors from a turn are fatal, unless the deck ran out and the rules say that simply ends
+    /// the round.
+    fn round_ended_early(&self, err: Error) -> Result<()> {
+        if err == Error::DeckExhausted && self.game.is_round_over() {
+            Ok(())
+        } else {
+            Err(err)
+        }
+    }
+
+    /// Play a single normal turn for the current player. Returns `true` if the player went out.
+    fn play_turn(&mut self) -> Result<bool> {
+        let seat = self.game.current_player_index();
+        let card = self.draw(seat)?;
+        let action = self.players[seat].your_turn(&view(&self.game, seat), card);
+        let went_out = matches!(action, Action::GoOut(_, _));
+        self.game.turn(action.clone())?;
+        for other in self.others(seat) {
+            self.players[other].other_player_turn(&view(&self.game, other), action.clone());
+        }
+        Ok(went_out)
+    }
+
+    fn play_last_turn(&mut self) -> Result<()> {
+        let seat = self.game.current_player_index();
+        let card = self.draw(seat)?;
+        let action = self.players[seat].your_last_turn(&view(&self.game, seat), card);
+        self.game.last_turn(action.clone())?;
+        for other in self.others(seat) {
+            self.players[other].other_player_last_turn(&view(&self.game, other), action.clone());
+        }
+        Ok(())
+    }
+
+    /// Ask the player in `seat` where to draw from, then draw. If the draw pile has run out and
+    /// the rules say to take the discard instead, the player gets the top discard.
+    fn draw(&mut self, seat: usize) -> Result<Card> {
+        let mut location = self.players[seat].your_draw(&view(&self.game, seat));
+        let card = match self.game.draw(location) {
+            Err(Error::DeckExhausted)
+                if self.game.rules().exhaustion == ExhaustionPolicy::ForceDiscardDraw =>
+            {
+                location = DrawLocation::DiscardPile;
+                self.game.draw(location)
+            }
+            result => result,
+        }?;
+        for other in self.others(seat) {
+            self.players[other].other_player_draw(&view(&self.game, other), location);
+        }
+        Ok(card)
+    }
+
+    /// The seats of every player other than `seat`, in turn order.
+    fn others(&self, seat: usize) -> impl Iterator<Item = usize> {
+        let num_players = self.players.len();
+        (1..num_players).map(move |offset| (seat + offset) % num_players)
+    }
+}
+
+/// The runner has one player per seat in the game, so every seat it asks about exists.
+fn view(game: &Game, seat: usize) -> PlayerView {
+    game.view(seat)
+        .expect("GameRunner::new checks there is a player for every seat")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::card::DeckSpec;
+    use crate::game::EndOfRoundAction;
+    use crate::game_state::GameState;
+    use crate::rules::RuleSet;
+    use crate::score::Score;
+    use crate::test_utils::go_out_with_jokers;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// Goes out on its first turn with `go_out_with_jokers`.
+    struct GoOutPlayer {
+        log: Rc<RefCell<Vec<String>>>,
+    }
+
+    impl GoOutPlayer {
+        fn boxed(log: &Rc<RefCell<Vec<String>>>) -> Box<dyn Player> {
+            Box::new(GoOutPlayer {
+                log: Rc::clone(log),
+            })
+        }
+
+        fn log(&self, callback: &str) {
+            self.log.borrow_mut().push(callback.to_string());
+        }
+    }
+
+    impl Player for GoOutPlayer {
+        fn start_game(&mut self, view: &PlayerView) {
+            self.log("start_game");
+        }
+
+        fn start_round(&mut self, view: &PlayerView) {
+            self.log("start_round");
+        }
+
+        fn other_player_draw(&mut self, view: &PlayerView, draw_location: DrawLocation) {
+            self.log("other_player_draw");
+        }
+
+        fn other_player_turn(&mut self, view: &PlayerView, action: Action) {
+            self.log("other_player_turn");
+        }
+
+        fn other_player_last_turn(&mut self, view: &PlayerView, action: EndOfRoundAction) {
+            self.log("other_player_last_turn");
+        }
+
+        fn your_draw(&mut self, view: &PlayerView) -> DrawLocation {
+            self.log("your_draw");
+            assert!(view.is_my_turn());
+            if view.draw_pile_forbidden {
+                self.log("draw_pile_forbidden");
+            }
+            DrawLocation::DrawPile
+        }
+
+        fn your_turn(&mut self, view: &PlayerView, card: Card) -> Action {
+            self.log("your_turn");
+            assert!(view.hand.cards.contains(&card));
+            let (discard, groups) = go_out_with_jokers(&view.hand.cards, &view.game_state);
+            Action::GoOut(discard, groups)
+        }
+
+        fn your_last_turn(&mut self, view: &PlayerView, card: Card) -> EndOfRoundAction {
+            self.log("your_last_turn");
+            assert_eq!(view.first_out, Some(1 - view.seat));
+            let (discard, go_out) = go_out_with_jokers(&view.hand.cards, &view.game_state);
+            EndOfRoundAction {
+                discard,
+                go_out,
+                remaining: Vec::new(),
+            }
+        }
+
+        fn end_game(&mut self, view: &PlayerView, result: &GameResult) {
+            self.log("end_game");
+        }
+    }
+
+    fn all_jokers() -> DeckSpec {
+        DeckSpec::new(0, 200)
+    }
+
+    #[test]
+    fn test_run_full_match() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let players = vec![GoOutPlayer::boxed(&log), GoOutPlayer::boxed(&log)];
+        let result = GameRunner::new(players, Game::with_deck_spec(2, all_jokers()).unwrap())
+            .unwrap()
+            .run()
+            .unwrap();
+        let standings = &result.result.standings;
+        assert!(standings.iter().all(|standing| standing.score == Score(0)));
+        assert_eq!(result.result.winners().collect::<Vec<_>>(), vec![0, 1]);
+        assert_eq!(result.rounds.len(), 11);
+        // Whoever leads the round goes out straight away
+        for (index, round) in result.rounds.iter().enumerate() {
+            assert_eq!(round.first_out, Some(index % 2));
+        }
+    }
+
+    #[test]
+    fn test_callback_order() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let players = vec![GoOutPlayer::boxed(&log), GoOutPlayer::boxed(&log)];
+        GameRunner::new(players, Game::with_deck_spec(2, all_jokers()).unwrap())
+            .unwrap()
+            .run()
+            .unwrap();
+        let expected_round = [
+            "start_round",
+            "start_round",
+            "your_draw",
+            "other_player_draw",
+            "your_turn",
+            "other_player_turn",
+            "your_draw",
+            "other_player_draw",
+            "your_last_turn",
+            "other_player_last_turn",
+        ];
+        let log = log.borrow();
+        assert_eq!(&log[..2], &["start_game", "start_game"]);
+        assert_eq!(log.len(), 2 + 11 * expected_round.len() + 2);
+        assert_eq!(&log[log.len() - 2..], &["end_game", "end_game"]);
+        for round in log[2..log.len() - 2].chunks(expected_round.len()) {
+            assert_eq!(round, expected_round);
+        }
+    }
+
+    #[test]
+    fn test_force_discard_draw() {
+        // Exactly enough cards for the King round, so the draw pile is empty once it's dealt
+        let rules = RuleSet {
+            deck: DeckSpec::new(0, 27),
+            exhaustion: ExhaustionPolicy::ForceDiscardDraw,
+            ..RuleSet::official()
+        };
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let players = vec![GoOutPlayer::boxed(&log), GoOutPlayer::boxed(&log)];
+        let result = GameRunner::new(players, Game::with_rules(2, rules).unwrap())
+            .unwrap()
+            .run()
+            .unwrap();
+        assert_eq!(result.rounds.len(), 11);
+        assert_eq!(result.rounds[10].first_out, Some(0));
+        assert!(log.borrow().contains(&"draw_pile_forbidden".to_string()));
+    }
+
+    /// Always tries to discard a joker, whether or not it has one.
+    struct JokerDiscarder;
+
+    impl Player for JokerDiscarder {
+        fn start_game(&mut self, view: &PlayerView) {}
+
+        fn start_round(&mut self, view: &PlayerView) {}
+
+        fn other_player_draw(&mut self, view: &PlayerView, draw_location: DrawLocation) {}
+
+        fn other_player_turn(&mut self, view: &PlayerView, action: Action) {}
+
+        fn other_player_last_turn(&mut self, view: &PlayerView, action: EndOfRoundAction) {}
+
+        fn your_draw(&mut self, view: &PlayerView) -> DrawLocation {
+            DrawLocation::DrawPile
+        }
+
+        fn your_turn(&mut self, view: &PlayerView, card: Card) -> Action {
+            Action::Discard(Card::Joker)
+        }
+
+        fn your_last_turn(&mut self, view: &PlayerView, card: Card) -> EndOfRoundAction {
+            EndOfRoundAction {
+                discard: Card::Joker,
+                go_out: Vec::new(),
+                remaining: Vec::new(),
+            }
+        }
+
+        fn end_game(&mut self, view: &PlayerView, result: &GameResult) {}
+    }
+
+    #[test]
+    fn test_invalid_move() {
+        let players: Vec<Box<dyn Player>> =
+            vec![Box::new(JokerDiscarder), Box::new(JokerDiscarder)];
+        // No jokers in the deck
+        let game = Game::with_deck_spec(2, DeckSpec::new(1, 0)).unwrap();
+        let result = GameRunner::new(players, game).unwrap().run();
+        assert_eq!(result, Err(Error::CardNotInHand(Card::Joker)));
+    }
+
+    #[test]
+    fn test_mismatched_players() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let runner = GameRunner::new(
+            vec![GoOutPlayer::boxed(&log)],
+            Game::with_deck_spec(2, all_jokers()).unwrap(),
+        );
+        assert_eq!(
+            runner.err(),
+            Some(Error::PlayerSeatMismatch {
+                players: 1,
+                seats: 2
+            })
+        );
+    }
+}
@@ -1,12 +1,11 @@
 use crate::card::{Card, NormalCard};
 use crate::game_state::GameState;
 use crate::rank::Rank;
+#[cfg(feature = "serde")]
+use crate::rules::RuleSet;
 use crate::suit::Suit;
 
-const MIN_CARDS: usize = 3;
-const MIN_CARDS_RUN: usize = MIN_CARDS;
 const MAX_CARDS_RUN: usize = crate::rank::NUM_RANKS;
-const MIN_CARDS_SET: usize = MIN_CARDS;
 
 pub type Result<T> = std::result::Result<T, Error>;
 
@@ -21,6 +20,7 @@ pub enum Error {
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ScoreGroup {
     Run(Run),
     Set(Set),
@@ -54,9 +54,31 @@ enum SetInfo {
     AllWilds,
 }
 
+impl ScoreGroup {
+    pub fn cards(&self) -> &[Card] {
+        match self {
+            ScoreGroup::Run(run) => run.cards(),
+            ScoreGroup::Set(set) => set.cards(),
+        }
+    }
+
+    /// Check that this group is still valid for `game_state`. Groups are validated when they are
+    /// built, but they may have been built for a different round.
+    pub fn validate(&self, game_state: &GameState) -> Result<()> {
+        match self {
+            ScoreGroup::Run(run) => Run::try_from(run.cards(), game_state).map(|_| ()),
+            ScoreGroup::Set(set) => Set::try_from(set.cards(), game_state).map(|_| ()),
+        }
+    }
+}
+
 impl Run {
+    pub fn cards(&self) -> &[Card] {
+        &self.cards
+    }
+
     pub fn try_from(cards: &[Card], game_state: &GameState) -> Result<Run> {
-        if cards.len() < MIN_CARDS_RUN {
+        if cards.len() < game_state.rules().min_cards_run {
             return Err(Error::TooFewCards);
         }
         if cards.len() > MAX_CARDS_RUN {
@@ -96,6 +118,51 @@ impl Run {
             info,
         })
     }
+
+    /// Like `try_from`, but the cards may be in any order. The naturals are sorted by rank, wilds
+    /// fill the gaps between them, and any spare wilds extend the run upwards, then downwards
+    /// once it reaches a king. The returned run holds the cards in rank order.
+    pub fn try_from_unordered(cards: &[Card], game_state: &GameState) -> Result<Run> {
+        if cards.len() < game_state.rules().min_cards_run {
+            return Err(Error::TooFewCards);
+        }
+        if cards.len() > MAX_CARDS_RUN {
+            return Err(Error::TooManyCards);
+        }
+
+        let mut naturals: Vec<NormalCard> = non_wilds(cards, game_state).collect();
+        naturals.sort_by_key(|card| card.rank());
+        let (low_rank, high_rank) = match (naturals.first(), naturals.last()) {
+            (Some(low), Some(high)) => (low.rank(), high.rank()),
+            _ => return Run::try_from(cards, game_state),
+        };
+        if !all_match_expected_suit(cards, game_state, naturals[0].suit()) {
+            return Err(Error::NotAllSameSuit);
+        }
+        let span = low_rank.steps_to(high_rank) as usize + 1;
+        let has_duplicate_rank = naturals.windows(2).any(|w| w[0].rank() == w[1].rank());
+        if span > cards.len() || has_duplicate_rank {
+            return Err(Error::NotAllInOrder);
+        }
+
+        let spare = cards.len() - span;
+        let above = spare.min(high_rank.steps_to(Rank::King) as usize);
+        let low_rank = low_rank.minus(spare - above).ok_or(Error::OutOfRange)?;
+
+        let mut naturals = naturals.into_iter().peekable();
+        let mut wilds = cards
+            .iter()
+            .cloned()
+            .filter(|&card| game_state.is_card_wild(card));
+        let ordered: Vec<Card> = Rank::range(low_rank, Rank::King)
+            .take(cards.len())
+            .filter_map(|rank| match naturals.next_if(|card| card.rank() == rank) {
+                Some(card) => Some(Card::Normal(card)),
+                None => wilds.next(),
+            })
+            .collect();
+        Run::try_from(&ordered, game_state)
+    }
 }
 
 fn first_non_wild(cards: &[Card], game_state: &GameState) -> Option<NormalCard> {
@@ -156,8 +223,12 @@ fn all_match_expected_rank(cards: &[Card], game_state: &GameState, rank: Rank) -
 }
 
 impl Set {
+    pub fn cards(&self) -> &[Card] {
+        &self.cards
+    }
+
     pub fn try_from(cards: &[Card], game_state: &GameState) -> Result<Set> {
-        if cards.len() < MIN_CARDS_SET {
+        if cards.len() < game_state.rules().min_cards_set {
             return Err(Error::TooFewCards);
         }
 
@@ -186,6 +257,65 @@ impl Set {
     }
 }
 
+/// Build a group without knowing its round, e.g. when deserializing it. The cards only have to make
+/// a valid group of any size in some round; `Game::turn` and `Game::last_turn` check it against
+/// the actual round.
+#[cfg(feature = "serde")]
+fn try_from_any_round<T>(build: impl Fn(&GameState) -> Result<T>) -> Result<T> {
+    let rules = RuleSet {
+        min_cards_run: 1,
+        min_cards_set: 1,
+        ..RuleSet::official()
+    };
+    let first = GameState::with_rules(Rank::Three, rules);
+    std::iter::successors(Some(first), |game_state| game_state.next())
+        .map(|game_state| build(&game_state))
+        .find(Result::is_ok)
+        .unwrap_or_else(|| build(&first))
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Run {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        serde::Serialize::serialize(&self.cards, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Run {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Self, D::Error> {
+        let cards = <Vec<Card> as serde::Deserialize>::deserialize(deserializer)?;
+        try_from_any_round(|game_state| Run::try_from(&cards, game_state))
+            .map_err(|e| serde::de::Error::custom(format!("not a run: {:?}", e)))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Set {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        serde::Serialize::serialize(&self.cards, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Set {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Self, D::Error> {
+        let cards = <Vec<Card> as serde::Deserialize>::deserialize(deserializer)?;
+        try_from_any_round(|game_state| Set::try_from(&cards, game_state))
+            .map_err(|e| serde::de::Error::custom(format!("not a set: {:?}", e)))
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -420,6 +550,77 @@ mod test {
         assert_eq!(result, Err(Error::TooFewCards));
     }
 
+    #[test]
+    fn try_from_house_rule_min_cards() {
+        use crate::rules::RuleSet;
+        let rules = RuleSet {
+            min_cards_run: 4,
+            min_cards_set: 2,
+            ..RuleSet::official()
+        };
+        let game_state = GameState::with_rules(Rank::Six, rules);
+        assert_eq!(
+            Run::try_from(&cards_from_str("3S,4S,5S"), &game_state),
+            Err(Error::TooFewCards)
+        );
+        assert!(Run::try_from(&cards_from_str("3S,4S,5S,6S"), &game_state).is_ok());
+        assert!(Set::try_from(&cards_from_str("8D,8H"), &game_state).is_ok());
+    }
+
+    #[test]
+    fn score_group_validate() -> Result<()> {
+        let cards = cards_from_str("6H,QD,QR");
+        let group = ScoreGroup::Set(Set::try_from(&cards, &GameState::new(Rank::Six))?);
+        assert_eq!(group.cards(), &cards[..]);
+        assert_eq!(group.validate(&GameState::new(Rank::Six)), Ok(()));
+        assert_eq!(
+            group.validate(&GameState::new(Rank::Three)),
+            Err(Error::NotAllSameRank)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn run_try_from_unordered() -> Result<()> {
+        let game_state = GameState::new(Rank::Six);
+        for string in ["7H,Joker,5H", "5H,7H,Joker", "Joker,7H,5H"] {
+            let run = Run::try_from_unordered(&cards_from_str(string), &game_state)?;
+            assert_eq!(run.cards(), &cards_from_str("5H,Joker,7H")[..]);
+        }
+
+        // Spare wilds extend the run upwards, then downwards once it reaches a king
+        let run = Run::try_from_unordered(&cards_from_str("6S,9C,8C,Joker"), &game_state)?;
+        assert_eq!(run.cards(), &cards_from_str("8C,9C,6S,Joker")[..]);
+        let run = Run::try_from_unordered(&cards_from_str("KD,Joker,6S,QD"), &game_state)?;
+        assert_eq!(run.cards(), &cards_from_str("Joker,6S,QD,KD")[..]);
+        assert_eq!(
+            RunInfo::Normal {
+                suit: Suit::Diamond,
+                low_rank: Rank::Ten,
+                high_rank: Rank::King
+            },
+            run.info
+        );
+
+        let run = Run::try_from_unordered(&cards_from_str("Joker,6S,Joker"), &game_state)?;
+        assert_eq!(run.info, RunInfo::AllWilds);
+        Ok(())
+    }
+
+    #[test]
+    fn run_try_from_unordered_errors() {
+        let game_state = GameState::new(Rank::Six);
+        let try_from = |string| Run::try_from_unordered(&cards_from_str(string), &game_state);
+        assert_eq!(try_from("5H,7H,8H"), Err(Error::NotAllInOrder));
+        assert_eq!(try_from("5H,5H,Joker"), Err(Error::NotAllInOrder));
+        assert_eq!(try_from("5H,7D,Joker"), Err(Error::NotAllSameSuit));
+        assert_eq!(try_from("5H,Joker"), Err(Error::TooFewCards));
+        assert_eq!(
+            try_from("3H,4H,5H,6H,7H,8H,9H,10H,JH,QH,KH,Joker"),
+            Err(Error::TooManyCards)
+        );
+    }
+
     fn cards_from_str(string: &str) -> Vec<Card> {
         use crate::hand::Hand;
         Hand::try_from(string).unwrap().cards.to_vec()
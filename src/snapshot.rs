@@ -0,0 +1,461 @@
+use crate::card::Card;
+use crate::game::{Phase, PlayerInfo};
+use crate::game_state::GameState;
+use crate::rank::Rank;
+use crate::record::{self, Move};
+use crate::round_result::{PlayerRoundResult, RoundResult};
+use crate::rules::RuleSet;
+use crate::score::Score;
+use std::fmt;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub enum Error {
+    /// The line (counting from 1) could not be parsed.
+    InvalidLine(usize),
+    /// The snapshot is missing a line starting with this keyword.
+    Missing(&'static str),
+}
+
+/// The full state of an in-progress game, including the hidden parts, so that it can be saved and
+/// restored with `Game::snapshot` and `Game::from_snapshot`.
+///
+/// The text form has one item per line, in this order:
+///
+/// ```text
+/// seed 1234
+/// shuffles 3
+/// rule exhaustion fresh-deck
+/// round 4
+/// dealer 1
+/// current 0
+/// first-out none
+/// phase awaiting-discard
+/// deck 7H,Joker,QS
+/// discard-pile 3C,5D
+/// player 0 4S,4D,9C,Joker,KR
+/// player 12 10S,JH,3R,5S
+/// round-result 3 0 1
+/// round-player 12 set:5H,5C,Joker left:QD,KS
+/// round-player 0 run:3S,4S,5S
+/// move draw pile
+/// ```
+///
+/// Cards in the deck and discard pile are listed from the bottom up. Each `player` line gives a
+/// seat's score and hand, and each `round-result` is followed by a `round-player` line for every
+/// seat. Anybody who has already gone out or had their last turn in the current round has a
+/// `player-round` line with their seat, points and melds. The moves are kept so that the game can
+/// still be recorded and undone.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Snapshot {
+    pub seed: u64,
+    pub num_shuffles: u64,
+    pub rules: RuleSet,
+    pub round_rank: Rank,
+    pub dealer: usize,
+    pub current_player: usize,
+    pub first_out: Option<usize>,
+    pub phase: Phase,
+    /// The last card is the next one drawn.
+    pub deck: Vec<Card>,
+    /// The last card is on top.
+    pub discard_pile: Vec<Card>,
+    pub players: Vec<PlayerInfo>,
+    /// Each player's result for the current round, once they have gone out or had their last turn.
+    pub round_melds: Vec<Option<PlayerRoundResult>>,
+    pub round_results: Vec<RoundResult>,
+    pub moves: Vec<Move>,
+}
+
+impl Snapshot {
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut seed = None;
+        let mut num_shuffles = None;
+        let mut rules = RuleSet::official();
+        let mut round_rank = None;
+        let mut dealer = None;
+        let mut current_player = None;
+        let mut first_out = None;
+        let mut phase = None;
+        let mut deck = None;
+        let mut discard_pile = None;
+        let mut players = Vec::new();
+        let mut round_melds = Vec::new();
+        let mut round_results = Vec::<RoundResult>::new();
+        let mut moves = Vec::new();
+        let mut move_state = None;
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = || Error::InvalidLine(index + 1);
+            let mut words = line.split_whitespace();
+            let keyword = words.next().ok_or_else(invalid)?;
+            let args = words.collect::<Vec<_>>();
+            match (keyword, args.as_slice()) {
+                ("seed", [n]) => seed = Some(n.parse().map_err(|_| invalid())?),
+                ("shuffles", [n]) => num_shuffles = Some(n.parse().map_err(|_| invalid())?),
+                ("rule", [name, value]) => {
+                    record::parse_rule(&mut rules, name, value).ok_or_else(invalid)?
+                }
+                ("round", [rank]) => round_rank = Some(Rank::try_from(rank).ok_or_else(invalid)?),
+                ("dealer", [seat]) => dealer = Some(seat.parse().map_err(|_| invalid())?),
+                ("current", [seat]) => current_player = Some(seat.parse().map_err(|_| invalid())?),
+                ("first-out", ["none"]) => first_out = Some(None),
+                ("first-out", [seat]) => {
+                    first_out = Some(Some(seat.parse().map_err(|_| invalid())?))
+                }
+                ("phase", [name]) => phase = Some(parse_phase(name).ok_or_else(invalid)?),
+                ("deck", cards) => deck = Some(parse_card_list(cards).ok_or_else(invalid)?),
+                ("discard-pile", cards) => {
+                    discard_pile = Some(parse_card_list(cards).ok_or_else(invalid)?)
+                }
+                ("player", [score, cards @ ..]) => players.push(PlayerInfo {
+                    hand: parse_card_list(cards).ok_or_else(invalid)?,
+                    score: Score(score.parse().map_err(|_| invalid())?),
+                }),
+                ("player-round", [seat, rest @ ..]) => {
+                    let rank = round_rank.ok_or_else(invalid)?;
+                    let seat = seat.parse::<usize>().map_err(|_| invalid())?;
+                    let result = parse_player_result(rest, &GameState::with_rules(rank, rules))
+                        .ok_or_else(invalid)?;
+                    round_melds.push((index + 1, seat, result));
+                }
+                ("round-result", [rank, dealer, first_out]) => round_results.push(RoundResult {
+                    round_rank: Rank::try_from(rank).ok_or_else(invalid)?,
+                    dealer: dealer.parse().map_err(|_| invalid())?,
+                    first_out: parse_seat_or_none(first_out).ok_or_else(invalid)?,
+                    players: Vec::new(),
+                }),
+                ("round-player", rest) => {
+                    let round = round_results.last_mut().ok_or_else(invalid)?;
+                    let game_state = GameState::with_rules(round.round_rank, rules);
+                    let result = parse_player_result(rest, &game_state).ok_or_else(invalid)?;
+                    round.players.push(result);
+                }
+                ("move", [keyword, rest @ ..]) => {
+                    // Like a game record, the moves start from the first round
+                    let state = move_state.get_or_insert(GameState::with_rules(Rank::Three, rules));
+                    let mv = record::parse_move(keyword, rest, state).ok_or_else(invalid)?;
+                    if mv == Move::NextRound {
+                        *state = state.next().unwrap_or(*state);
+                    }
+                    moves.push(mv);
+                }
+                _ => return Err(invalid()),
+            }
+        }
+        let mut melds = vec![None; players.len()];
+        for (line, seat, result) in round_melds {
+            *melds.get_mut(seat).ok_or(Error::InvalidLine(line))? = Some(result);
+        }
+        Ok(Snapshot {
+            seed: seed.ok_or(Error::Missing("seed"))?,
+            num_shuffles: num_shuffles.ok_or(Error::Missing("shuffles"))?,
+            rules,
+            round_rank: round_rank.ok_or(Error::Missing("round"))?,
+            dealer: dealer.ok_or(Error::Missing("dealer"))?,
+            current_player: current_player.ok_or(Error::Missing("current"))?,
+            first_out: first_out.ok_or(Error::Missing("first-out"))?,
+            phase: phase.ok_or(Error::Missing("phase"))?,
+            deck: deck.ok_or(Error::Missing("deck"))?,
+            discard_pile: discard_pile.ok_or(Error::Missing("discard-pile"))?,
+            players,
+            round_melds: melds,
+            round_results,
+            moves,
+        })
+    }
+}
+
+impl fmt::Display for Snapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "seed {}", self.seed)?;
+        writeln!(f, "shuffles {}", self.num_shuffles)?;
+        record::write_rules(f, &self.rules)?;
+        writeln!(f, "round {}", self.round_rank)?;
+        writeln!(f, "dealer {}", self.dealer)?;
+        writeln!(f, "current {}", self.current_player)?;
+        writeln!(f, "first-out {}", seat_or_none(self.first_out))?;
+        writeln!(f, "phase {}", phase_name(self.phase))?;
+        writeln!(f, "deck {}", record::join_cards(&self.deck))?;
+        writeln!(f, "discard-pile {}", record::join_cards(&self.discard_pile))?;
+        for player in self.players.iter() {
+            writeln!(
+                f,
+                "player {} {}",
+                player.score,
+                record::join_cards(&player.hand)
+            )?;
+        }
+        for (seat, result) in self.round_melds.iter().enumerate() {
+            if let Some(result) = result {
+                write!(f, "player-round {}", seat)?;
+                write_player_result(f, result)?;
+            }
+        }
+        for round in self.round_results.iter() {
+            writeln!(
+                f,
+                "round-result {} {} {}",
+                round.round_rank,
+                round.dealer,
+                seat_or_none(round.first_out)
+            )?;
+            for result in round.players.iter() {
+                write!(f, "round-player")?;
+                write_player_result(f, result)?;
+            }
+        }
+        for mv in self.moves.iter() {
+            writeln!(f, "move {}", mv)?;
+        }
+        Ok(())
+    }
+}
+
+fn write_player_result(f: &mut fmt::Formatter<'_>, result: &PlayerRoundResult) -> fmt::Result {
+    write!(f, " {}", result.points)?;
+    record::write_melds(f, &result.melds, &result.leftover)?;
+    writeln!(f)
+}
+
+fn parse_player_result(args: &[&str], game_state: &GameState) -> Option<PlayerRoundResult> {
+    let (points, rest) = args.split_first()?;
+    let (melds, leftover) = record::parse_melds(rest, game_state)?;
+    Some(PlayerRoundResult {
+        melds,
+        leftover,
+        points: Score(points.parse().ok()?),
+    })
+}
+
+/// Cards written with `record::join_cards`, which is nothing at all for no cards.
+fn parse_card_list(args: &[&str]) -> Option<Vec<Card>> {
+    match args {
+        [] => Some(Vec::new()),
+        [cards] => record::parse_cards(cards),
+        _ => None,
+    }
+}
+
+fn seat_or_none(seat: Option<usize>) -> String {
+    match seat {
+        Some(seat) => seat.to_string(),
+        None => "none".to_string(),
+    }
+}
+
+fn parse_seat_or_none(string: &str) -> Option<Option<usize>> {
+    match string {
+        "none" => Some(None),
+        _ => string.parse().ok().map(Some),
+    }
+}
+
+fn phase_name(phase: Phase) -> &'static str {
+    match phase {
+        Phase::AwaitingDraw => "awaiting-draw",
+        Phase::AwaitingDiscard => "awaiting-discard",
+        Phase::LastTurns { drawn: false } => "last-turns",
+        Phase::LastTurns { drawn: true } => "last-turns-drawn",
+        Phase::RoundOver => "round-over",
+        Phase::GameOver => "game-over",
+    }
+}
+
+fn parse_phase(name: &str) -> Option<Phase> {
+    Some(match name {
+        "awaiting-draw" => Phase::AwaitingDraw,
+        "awaiting-discard" => Phase::AwaitingDiscard,
+        "last-turns" => Phase::LastTurns { drawn: false },
+        "last-turns-drawn" => Phase::LastTurns { drawn: true },
+        "round-over" => Phase::RoundOver,
+        "game-over" => Phase::GameOver,
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::card::DeckSpec;
+    use crate::game::{self, Action, DrawLocation, EndOfRoundAction, Game};
+    use crate::test_utils::{go_out_with_jokers, play_round_going_out};
+
+    fn save_and_load(game: &Game) -> Game {
+        let text = game.snapshot().to_string();
+        Game::from_snapshot(Snapshot::parse(&text).unwrap()).unwrap()
+    }
+
+    /// Draw and discard the first card, `turns` times.
+    fn discard_turns(game: &mut Game, turns: usize) {
+        for _ in 0..turns {
+            game.draw(DrawLocation::DrawPile).unwrap();
+            game.discard(game.cur_player().hand[0]).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_round_trip_mid_round() {
+        let mut game = Game::with_seed(3, 21).unwrap();
+        discard_turns(&mut game, 60);
+        game.draw(DrawLocation::DiscardPile).unwrap();
+        let mut loaded = save_and_load(&game);
+        assert_eq!(loaded.snapshot(), game.snapshot());
+        assert_eq!(loaded.phase(), game::Phase::AwaitingDiscard);
+
+        // Both carry on the same way, reshuffles included
+        let card = game.cur_player().hand[1];
+        game.discard(card).unwrap();
+        loaded.discard(card).unwrap();
+        discard_turns(&mut game, 100);
+        discard_turns(&mut loaded, 100);
+        assert_eq!(loaded.snapshot(), game.snapshot());
+        assert_eq!(loaded.record(), game.record());
+    }
+
+    #[test]
+    fn test_round_trip_between_rounds() {
+        let mut game = Game::with_deck_spec(3, DeckSpec::new(0, 100)).unwrap();
+        play_round_going_out(&mut game);
+        let mut loaded = save_and_load(&game);
+        assert_eq!(loaded.phase(), game::Phase::RoundOver);
+        assert_eq!(loaded.snapshot(), game.snapshot());
+        assert_eq!(loaded.next_round(), Ok(true));
+        assert_eq!(game.next_round(), Ok(true));
+        assert_eq!(loaded.snapshot(), game.snapshot());
+
+        loop {
+            play_round_going_out(&mut game);
+            if !game.next_round().unwrap() {
+                break;
+            }
+        }
+        let loaded = save_and_load(&game);
+        assert_eq!(loaded.phase(), game::Phase::GameOver);
+        assert_eq!(loaded.snapshot(), game.snapshot());
+        assert_eq!(loaded.result(), game.result());
+    }
+
+    #[test]
+    fn test_round_trip_big_deck() {
+        let game = Game::with_deck_spec(2, DeckSpec::new(0, 300)).unwrap();
+        assert_eq!(save_and_load(&game).snapshot(), game.snapshot());
+    }
+
+    #[test]
+    fn test_round_trip_last_turns() {
+        let mut game = Game::with_deck_spec(3, DeckSpec::new(0, 100)).unwrap();
+        // Play a round where everybody goes out, then go out in the next round
+        play_round_going_out(&mut game);
+        game.next_round().unwrap();
+        game.draw(DrawLocation::DrawPile).unwrap();
+        let (discard, go_out) = go_out_with_jokers(&game.cur_player().hand, &game.state());
+        game.turn(Action::GoOut(discard, go_out)).unwrap();
+        game.draw(DrawLocation::DrawPile).unwrap();
+        assert_eq!(game.phase(), game::Phase::LastTurns { drawn: true });
+        let snapshot = game.snapshot();
+        assert_eq!(snapshot.round_results.len(), 1);
+        assert!(snapshot.round_melds[1].is_some());
+
+        let mut loaded = save_and_load(&game);
+        assert_eq!(loaded.snapshot(), snapshot);
+        // Take a last turn keeping everything
+        let hand = loaded.cur_player().hand.clone();
+        loaded
+            .last_turn(EndOfRoundAction {
+                discard: hand[0],
+                go_out: Vec::new(),
+                remaining: hand[1..].to_vec(),
+            })
+            .unwrap();
+        assert_eq!(loaded.phase(), game::Phase::LastTurns { drawn: false });
+        assert_eq!(loaded.current_player_index(), 0);
+        assert_eq!(loaded.players()[2].score, Score(200));
+        // The moves from before saving are still there to undo
+        loaded.undo().unwrap();
+        assert_eq!(loaded.snapshot(), snapshot);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let text = Game::with_seed(2, 1).unwrap().snapshot().to_string();
+        assert!(Snapshot::parse(&text).is_ok());
+        let without = |keyword: &str| {
+            text.lines()
+                .filter(|line| !line.starts_with(keyword))
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        assert_eq!(
+            Snapshot::parse(&without("seed ")),
+            Err(Error::Missing("seed"))
+        );
+        assert_eq!(
+            Snapshot::parse(&without("deck ")),
+            Err(Error::Missing("deck"))
+        );
+        assert_eq!(
+            Snapshot::parse(&text.replace("phase awaiting-draw", "phase thinking")),
+            Err(Error::InvalidLine(18))
+        );
+        assert_eq!(
+            Snapshot::parse(&format!("{}player-round 5 0\n", text)),
+            Err(Error::InvalidLine(text.lines().count() + 1))
+        );
+    }
+
+    #[test]
+    fn test_from_snapshot_errors() {
+        let snapshot = Game::with_seed(2, 1).unwrap().snapshot();
+        let mut players = snapshot.players.clone();
+        players.pop();
+        assert_eq!(
+            Game::from_snapshot(Snapshot {
+                players,
+                ..snapshot.clone()
+            })
+            .err(),
+            Some(game::Error::InvalidPlayerCount(1))
+        );
+        assert_eq!(
+            Game::from_snapshot(Snapshot {
+                current_player: 2,
+                ..snapshot.clone()
+            })
+            .err(),
+            Some(game::Error::InvalidSnapshot)
+        );
+        // Last turns without anybody having gone out would never end
+        assert_eq!(
+            Game::from_snapshot(Snapshot {
+                phase: game::Phase::LastTurns { drawn: false },
+                ..snapshot.clone()
+            })
+            .err(),
+            Some(game::Error::InvalidSnapshot)
+        );
+        let mut deck = snapshot.deck.clone();
+        deck.push(Card::Joker);
+        assert_eq!(
+            Game::from_snapshot(Snapshot {
+                deck,
+                ..snapshot.clone()
+            })
+            .err(),
+            Some(game::Error::InvalidSnapshot)
+        );
+        // A different seed deals different cards, so the moves no longer lead to this position
+        assert_eq!(
+            Game::from_snapshot(Snapshot {
+                seed: 2,
+                ..snapshot
+            })
+            .err(),
+            Some(game::Error::InvalidSnapshot)
+        );
+    }
+}
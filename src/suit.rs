@@ -1,4 +1,6 @@
+use crate::parse::{ParseError, ParseErrorKind};
 use std::fmt;
+use std::str::FromStr;
 
 #[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Copy, Clone)]
 pub enum Suit {
@@ -10,6 +12,17 @@ pub enum Suit {
 }
 
 impl Suit {
+    /// Position in `ALL_SUITS`.
+    pub(crate) fn index(self) -> usize {
+        match self {
+            Suit::Spade => 0,
+            Suit::Club => 1,
+            Suit::Heart => 2,
+            Suit::Diamond => 3,
+            Suit::Star => 4,
+        }
+    }
+
     pub fn try_from(c: char) -> Option<Self> {
         Some(match c {
             'S' => Suit::Spade,
@@ -20,6 +33,66 @@ impl Suit {
             _ => return None,
         })
     }
+
+    /// Like `try_from`, but also accepts lowercase letters and suit glyphs, filled or outlined.
+    pub fn try_from_lenient(c: char) -> Option<Self> {
+        Some(match c {
+            '\u{2660}' | '\u{2664}' => Suit::Spade,
+            '\u{2663}' | '\u{2667}' => Suit::Club,
+            '\u{2665}' | '\u{2661}' => Suit::Heart,
+            '\u{2666}' | '\u{2662}' => Suit::Diamond,
+            '\u{2605}' | '\u{2606}' => Suit::Star,
+            _ => return Suit::try_from(c.to_ascii_uppercase()),
+        })
+    }
+
+    /// The suit's symbol, e.g. '\u{2660}' for spades.
+    pub fn glyph(self) -> char {
+        match self {
+            Suit::Spade => '\u{2660}',
+            Suit::Club => '\u{2663}',
+            Suit::Heart => '\u{2665}',
+            Suit::Diamond => '\u{2666}',
+            Suit::Star => '\u{2605}',
+        }
+    }
+
+    /// The plural name of the suit, e.g. "Spades".
+    pub fn name(self) -> &'static str {
+        match self {
+            Suit::Spade => "Spades",
+            Suit::Club => "Clubs",
+            Suit::Heart => "Hearts",
+            Suit::Diamond => "Diamonds",
+            Suit::Star => "Stars",
+        }
+    }
+}
+
+impl FromStr for Suit {
+    type Err = ParseError;
+
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        let mut chars = string.chars();
+        match (chars.next().and_then(Suit::try_from), chars.next()) {
+            (Some(suit), None) => Ok(suit),
+            _ => Err(ParseError::new(string, ParseErrorKind::BadSuit)),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Suit {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        crate::utils::serialize_display(self, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Suit {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        crate::utils::deserialize_from_str(deserializer)
+    }
 }
 
 impl fmt::Display for Suit {
@@ -59,4 +132,17 @@ mod test {
         assert_eq!(None, Suit::try_from('E'));
         assert_eq!(None, Suit::try_from('F'));
     }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!(Ok(Suit::Star), "R".parse());
+        assert_eq!(
+            Err(ParseError::new("RR", ParseErrorKind::BadSuit)),
+            "RR".parse::<Suit>()
+        );
+        assert_eq!(
+            Err(ParseError::new("", ParseErrorKind::BadSuit)),
+            "".parse::<Suit>()
+        );
+    }
 }
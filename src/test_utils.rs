@@ -0,0 +1,30 @@
+use crate::card::Card;
+use crate::game::{Action, DrawLocation, EndOfRoundAction, Game, Phase};
+use crate::game_state::GameState;
+use crate::score_group::{ScoreGroup, Set};
+
+/// Go out by discarding the first card of `hand` and melding the rest as a single set. Only works
+/// with a deck made entirely of jokers.
+pub fn go_out_with_jokers(hand: &[Card], game_state: &GameState) -> (Card, Vec<ScoreGroup>) {
+    let set = Set::try_from(&hand[1..], game_state).unwrap();
+    (hand[0], vec![ScoreGroup::Set(set)])
+}
+
+/// Play out the rest of the round with every player drawing from the draw pile and going out with
+/// `go_out_with_jokers`.
+pub fn play_round_going_out(game: &mut Game) {
+    while !game.is_round_over() {
+        game.draw(DrawLocation::DrawPile).unwrap();
+        let (discard, go_out) = go_out_with_jokers(&game.cur_player().hand, &game.state());
+        match game.phase() {
+            Phase::AwaitingDiscard => game.turn(Action::GoOut(discard, go_out)).unwrap(),
+            _ => game
+                .last_turn(EndOfRoundAction {
+                    discard,
+                    go_out,
+                    remaining: Vec::new(),
+                })
+                .unwrap(),
+        }
+    }
+}
@@ -1,3 +1,8 @@
+#[cfg(feature = "serde")]
+use std::fmt;
+#[cfg(feature = "serde")]
+use std::str::FromStr;
+
 pub fn split_first_char(string: &str) -> Option<(char, String)> {
     let mut chars = string.chars();
     let first_char = chars.next()?;
@@ -17,6 +22,28 @@ pub fn split_last_char(string: &str) -> Option<(String, char)> {
     Some((rest, last_char))
 }
 
+/// Serialize `value` as the string it displays as.
+#[cfg(feature = "serde")]
+pub(crate) fn serialize_display<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: fmt::Display,
+    S: serde::Serializer,
+{
+    serializer.collect_str(value)
+}
+
+/// Deserialize a string and parse it with `FromStr`.
+#[cfg(feature = "serde")]
+pub(crate) fn deserialize_from_str<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+    D: serde::Deserializer<'de>,
+{
+    let string = <String as serde::Deserialize>::deserialize(deserializer)?;
+    string.parse().map_err(serde::de::Error::custom)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;